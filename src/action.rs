@@ -6,7 +6,9 @@ use serde::{
 };
 use strum::Display;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize)]
+use crate::mode::Mode;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Display, Deserialize)]
 pub enum Action {
     Tick,
     Render,
@@ -21,7 +23,64 @@ pub enum Action {
     Refresh,
     Enter,
     Escape,
+    PageUp,
+    PageDown,
+    /// Move between the panes or views of a screen.
+    NextPane,
+    PreviousGameweek,
+    NextGameweek,
+    Search,
+    /// Chart the selected player's price.
+    Chart,
+    /// Mark a player to swap in a what-if lineup, or swap with the marked one.
+    Swap,
+    /// Throw away the what-if lineup.
+    Discard,
+    /// Show or hide the suggested lineup.
+    ToggleSuggestion,
+    ApplySuggestion,
+    Sell,
+    Undo,
+    /// Start the draft again from the current team.
+    ResetDraft,
+    SaveDraft,
+    OpenDrafts,
+    DeleteDraft,
+    CyclePosition,
+    CycleTeam,
+    CycleAvailability,
+    LowerMinPrice,
+    RaiseMinPrice,
+    LowerMaxPrice,
+    RaiseMaxPrice,
+    CycleSort,
+    ReverseSort,
     Error(String),
+    /// A load that failed and why, so the component that asked for it can ask again.
+    RequestFailed(Box<Action>, String),
     Help,
     GetPlayerImage(i64),
+    LoadGameweek(i64),
+    LoadHistory,
+    SwitchMode(Mode),
+    /// A component wants every key, e.g. while typing a search, so global shortcuts are paused.
+    CaptureKeys(bool),
+    LoadClassicStandings(i64, i64),
+    ViewManager(String),
+    ViewOwnTeam,
+    /// Switch to the next profile in the config, in name order.
+    NextProfile,
+    LoadH2HStandings(i64, i64),
+    LoadH2HMatches(i64, i64),
+    LoadMatchup(i64, i64, i64),
+    /// Pick a player in the browser for the planner: `element_type` and the most that can be spent.
+    ChoosePlayer(i64, i64),
+    PlayerChosen(i64),
+}
+
+impl Action {
+    /// A [`Action::RequestFailed`] for `request`.
+    pub fn failed(request: Action, reason: String) -> Self {
+        Action::RequestFailed(Box::new(request), reason)
+    }
 }
@@ -0,0 +1,166 @@
+//! Response types for FPL API endpoints that `fpl_api` doesn't cover.
+
+use fpl_api::manager::{EntryHistory, Manager};
+use serde::{Deserialize, Serialize};
+
+// Manager history
+//
+// https://fantasy.premierleague.com/api/entry/{id}/history/
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManagerHistory {
+    /// One entry per gameweek played this season.
+    pub current: Vec<EntryHistory>,
+    pub past: Vec<PastSeason>,
+    pub chips: Vec<ChipPlayed>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PastSeason {
+    pub season_name: String,
+    pub total_points: i64,
+    pub rank: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChipPlayed {
+    pub name: String,
+    pub time: String,
+    pub event: i64,
+}
+
+// Classic league standings
+//
+// https://fantasy.premierleague.com/api/leagues-classic/{id}/standings/?page_standings={page}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassicStandings {
+    pub league: LeagueInfo,
+    pub standings: Page<ClassicEntry>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeagueInfo {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub has_next: bool,
+    pub page: i64,
+    pub results: Vec<T>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassicEntry {
+    pub id: i64,
+    pub event_total: i64,
+    pub player_name: String,
+    pub rank: i64,
+    pub last_rank: i64,
+    pub rank_sort: i64,
+    pub total: i64,
+    pub entry: i64,
+    pub entry_name: String,
+}
+
+// Head-to-head leagues
+//
+// https://fantasy.premierleague.com/api/leagues-h2h/{id}/standings/?page_standings={page}
+// https://fantasy.premierleague.com/api/leagues-h2h-matches/league/{id}/?page=1&event={gw}
+
+/// One of the manager's H2H leagues, as listed in their entry details.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct H2HLeague {
+    pub id: i64,
+    pub name: String,
+    pub entry_rank: i64,
+    pub entry_last_rank: i64,
+}
+
+impl H2HLeague {
+    /// The manager's H2H leagues, which `fpl_api` leaves as untyped JSON.
+    pub fn from_manager(manager: &Manager) -> Vec<H2HLeague> {
+        manager
+            .leagues
+            .h2h
+            .iter()
+            .filter_map(|v| {
+                match serde_json::from_value(v.clone()) {
+                    Ok(league) => Some(league),
+                    Err(e) => {
+                        log::warn!("Skipping unreadable H2H league {}: {}", v, e);
+                        None
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct H2HStandings {
+    pub league: LeagueInfo,
+    pub standings: Page<H2HEntry>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct H2HEntry {
+    pub id: i64,
+    pub player_name: String,
+    pub rank: i64,
+    pub last_rank: i64,
+    pub rank_sort: i64,
+    /// League points: three for a win and one for a draw.
+    pub total: i64,
+    /// `None` for the AVERAGE entry that evens out leagues with an odd number of managers.
+    pub entry: Option<i64>,
+    pub entry_name: String,
+    pub matches_played: i64,
+    pub matches_won: i64,
+    pub matches_drawn: i64,
+    pub matches_lost: i64,
+    pub points_for: i64,
+}
+
+/// A gameweek's matches in an H2H league.
+pub type H2HMatches = Page<H2HMatch>;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct H2HMatch {
+    pub id: i64,
+    pub event: i64,
+    pub entry_1_entry: Option<i64>,
+    pub entry_1_name: String,
+    pub entry_1_player_name: String,
+    pub entry_1_points: i64,
+    pub entry_2_entry: Option<i64>,
+    pub entry_2_name: String,
+    pub entry_2_player_name: String,
+    pub entry_2_points: i64,
+    pub winner: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_h2h_leagues_skip_unreadable_entries() {
+        let mut manager = Manager::default();
+        manager.leagues.h2h = vec![
+            json!({"id": 7, "name": "Office", "entry_rank": 2, "entry_last_rank": 3, "league_type": "c"}),
+            json!({"name": "Missing id"}),
+        ];
+        assert_eq!(H2HLeague::from_manager(&manager), vec![H2HLeague {
+            id: 7,
+            name: "Office".to_string(),
+            entry_rank: 2,
+            entry_last_rank: 3,
+        }]);
+    }
+}
@@ -1,8 +1,14 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-use bytes::Bytes;
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyEvent, KeyModifiers};
 use fpl_api;
 use image::{DynamicImage, ImageReader};
 use ratatui::prelude::Rect;
@@ -16,10 +22,16 @@ use tokio::sync::{mpsc, mpsc::UnboundedSender};
 
 use crate::{
     action::Action,
-    components::{fps::FpsCounter, home::Home, Component},
-    config::Config,
+    components::{
+        browser::PlayerBrowser, fixtures::Fixtures, fps::FpsCounter, help::Help, history::History, home::Home,
+        leagues::Leagues, planner::Planner, prices::Prices, Component,
+    },
+    config::{Config, Profile},
+    data_source::{DataSource, Snapshot},
     event::Event,
     mode::Mode,
+    player_photos::PlayerPhotos,
+    price_history::PriceStore,
     tui,
 };
 
@@ -32,27 +44,22 @@ pub struct App {
     pub should_suspend: bool,
     pub mode: Mode,
     pub last_tick_key_events: Vec<KeyEvent>,
-    fpl_client: fpl_api::FPLClient,
+    /// A component is taking text input, so the global shortcuts are off.
+    keys_captured: bool,
+    data_source: Arc<dyn DataSource>,
+    player_photos: PlayerPhotos,
+    price_store: PriceStore,
+    /// The manager whose team is on show, which is a rival's while looking around a league.
+    manager_id: String,
+    own_manager_id: String,
+    /// The config profile in use, if the app was opened with one.
+    profile: Option<(String, Profile)>,
+    gameweek: i64,
+    refreshing: Arc<AtomicBool>,
+    last_live_fetch: Option<Instant>,
     bootstrap_data: fpl_api::bootstrap::BootstrapData,
 }
 
-fn decode_bytes_to_image(data: Bytes) -> Result<DynamicImage, image::ImageError> {
-    ImageReader::new(std::io::Cursor::new(data)).with_guessed_format()?.decode()
-}
-
-async fn get_player_image(pc: i64, tx: UnboundedSender<Event>) {
-    let resp =
-        reqwest::get(format!("https://resources.premierleague.com/premierleague/photos/players/110x140/p{}.png", pc))
-            .await;
-    if let Ok(ok_resp) = resp {
-        if let Ok(bytes) = ok_resp.bytes().await {
-            if let Ok(image) = decode_bytes_to_image(bytes) {
-                let _ = tx.send(Event::PlayerImage(pc, image));
-            }
-        }
-    }
-}
-
 #[cfg(unix)]
 fn get_picker() -> Option<Picker> {
     Picker::from_query_stdio().ok()
@@ -90,27 +97,70 @@ fn get_picker() -> Option<Picker> {
 }
 
 impl App {
-    pub async fn new(tick_rate: f64, frame_rate: f64, player_id: String) -> Result<Self> {
+    pub async fn new(
+        config: Config,
+        tick_rate: f64,
+        frame_rate: f64,
+        player_id: String,
+        profile: Option<(String, Profile)>,
+        start_gameweek: Option<i64>,
+        data_source: Arc<dyn DataSource>,
+    ) -> Result<Self> {
         let fps = FpsCounter::default();
-        let config = Config::new()?;
         let mode = Mode::Home;
-        let fpl_client = fpl_api::FPLClient::new();
-        let bootstrap_data = fpl_client.get_bootstrap_data().await?;
-        let manager = fpl_client.get_manager_details(&player_id).await?;
-        let fixtures = fpl_client.get_fixtures().await?;
-        let gw_picks = fpl_client.get_manager_team_for_gw(&player_id, &manager.current_event.to_string()).await?;
+        let snapshot = Snapshot::load(data_source.as_ref(), &player_id, start_gameweek).await?;
         let ti = Self::load_team_images().await?;
-        let home = Home::new(manager, bootstrap_data.clone(), gw_picks, fixtures, get_picker(), ti);
+        let photos_dir = config.config._data_dir.join("photos");
+        let player_photos = match data_source.is_offline() {
+            true => PlayerPhotos::cached_only(photos_dir),
+            false => PlayerPhotos::new(photos_dir),
+        };
+        let price_store = PriceStore::new(config.config._data_dir.join("prices.jsonl"));
+        let gameweek = snapshot.manager.current_event;
+        let bootstrap_data = snapshot.bootstrap.clone();
+        let history = History::new(snapshot.manager.id);
+        let leagues = Leagues::new(&snapshot);
+        let fixtures = Fixtures::new(&snapshot);
+        let picker = get_picker();
+        let browser = PlayerBrowser::new(&snapshot, picker, ti.clone());
+        // The planner works from the squad as it stands, whichever gameweek Home opens on
+        let planner = match start_gameweek.filter(|gw| *gw != snapshot.manager.current_event) {
+            Some(_) => {
+                let gw_picks = data_source.get_manager_team_for_gw(&player_id, snapshot.manager.current_event).await?;
+                Planner::new(&Snapshot { gw_picks, ..snapshot.clone() })
+            },
+            None => Planner::new(&snapshot),
+        };
+        let prices = Prices::new(&snapshot);
+        let home = Home::new(snapshot, picker, ti);
         Ok(Self {
             tick_rate,
             frame_rate,
-            components: vec![Box::new(home)],
+            components: vec![
+                Box::new(home),
+                Box::new(history),
+                Box::new(leagues),
+                Box::new(fixtures),
+                Box::new(browser),
+                Box::new(planner),
+                Box::new(prices),
+                Box::new(Help::new()),
+            ],
             should_quit: false,
             should_suspend: false,
             config,
             mode,
             last_tick_key_events: Vec::new(),
-            fpl_client,
+            keys_captured: false,
+            data_source,
+            player_photos,
+            price_store,
+            manager_id: player_id.clone(),
+            own_manager_id: player_id,
+            profile,
+            gameweek,
+            refreshing: Arc::new(AtomicBool::new(false)),
+            last_live_fetch: None,
             bootstrap_data,
         })
     }
@@ -134,6 +184,10 @@ impl App {
         for component in self.components.iter_mut() {
             component.init(tui.size()?)?;
         }
+        self.price_store.record(&self.bootstrap_data, event_tx.clone(), action_tx.clone());
+        if let Some((name, profile)) = &self.profile {
+            event_tx.send(Event::ProfileSwitched(name.clone(), profile.clone()))?;
+        }
 
         loop {
             if let Some(e) = event_rx.recv().await {
@@ -142,8 +196,19 @@ impl App {
                     Event::Tick => action_tx.send(Action::Tick)?,
                     Event::Render => action_tx.send(Action::Render)?,
                     Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-                    Event::Key(key) => {
-                        if let KeyCode::Char('q') = key.code { action_tx.send(Action::Quit)? }
+                    Event::Key(key) if !self.keys_captured => self.handle_key_event(key, &action_tx)?,
+                    // While a component takes every key for typing, Ctrl keys can still quit or suspend
+                    Event::Key(key) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        let action = self.config.keybindings.action(self.mode, &[key]);
+                        if let Some(action @ (Action::Quit | Action::Suspend)) = action {
+                            action_tx.send(action.clone())?;
+                        }
+                    },
+                    Event::Refreshed(ref snapshot) => {
+                        self.gameweek = snapshot.manager.current_event;
+                        self.bootstrap_data = snapshot.bootstrap.clone();
+                        self.last_live_fetch = None;
+                        self.price_store.record(&self.bootstrap_data, event_tx.clone(), action_tx.clone());
                     },
                     _ => {},
                 }
@@ -161,14 +226,45 @@ impl App {
                 match action {
                     Action::Tick => {
                         self.last_tick_key_events.drain(..);
+                        let interval = Duration::from_secs(self.config.config.live_refresh_secs);
+                        if self.last_live_fetch.is_none_or(|t| t.elapsed() >= interval) {
+                            self.last_live_fetch = Some(Instant::now());
+                            self.fetch_live_data(event_tx.clone(), action_tx.clone());
+                        }
                     },
                     Action::Quit => self.should_quit = true,
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
                     Action::GetPlayerImage(player_code) => {
-                        let task_event = event_tx.clone();
-                        tokio::spawn(get_player_image(player_code, task_event));
+                        self.player_photos.fetch(player_code, event_tx.clone(), action_tx.clone());
+                    },
+                    Action::Refresh => self.refresh(event_tx.clone(), action_tx.clone()),
+                    Action::SwitchMode(mode) => self.mode = mode,
+                    Action::CaptureKeys(captured) => self.keys_captured = captured,
+                    Action::LoadHistory => self.load_history(event_tx.clone(), action_tx.clone()),
+                    Action::LoadClassicStandings(league_id, page) => {
+                        self.load_classic_standings(league_id, page, event_tx.clone(), action_tx.clone());
+                    },
+                    Action::LoadH2HStandings(league_id, page) => {
+                        self.load_h2h_standings(league_id, page, event_tx.clone(), action_tx.clone());
+                    },
+                    Action::LoadH2HMatches(league_id, gameweek) => {
+                        self.load_h2h_matches(league_id, gameweek, event_tx.clone(), action_tx.clone());
+                    },
+                    Action::LoadMatchup(gameweek, entry_1, entry_2) => {
+                        self.load_matchup(gameweek, [entry_1, entry_2], event_tx.clone(), action_tx.clone());
                     },
+                    Action::ViewManager(ref manager_id) => {
+                        self.view_manager(manager_id.clone(), event_tx.clone(), action_tx.clone())?;
+                    },
+                    Action::ViewOwnTeam if self.manager_id != self.own_manager_id => {
+                        self.view_manager(self.own_manager_id.clone(), event_tx.clone(), action_tx.clone())?;
+                    },
+                    Action::NextProfile => self.next_profile(event_tx.clone(), action_tx.clone())?,
+                    Action::LoadGameweek(gameweek) => {
+                        self.load_gameweek(gameweek, event_tx.clone(), action_tx.clone());
+                    },
+                    Action::Error(ref e) | Action::RequestFailed(_, ref e) => log::error!("{}", e),
                     Action::Resize(w, h) => {
                         tui.resize(Rect::new(0, 0, w, h))?;
                         tui.draw(|f| {
@@ -213,6 +309,231 @@ impl App {
         Ok(())
     }
 
+    /// Reload everything in the background; the result arrives as [`Event::Refreshed`].
+    fn refresh(&self, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) {
+        if self.refreshing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let data_source = self.data_source.clone();
+        let manager_id = self.manager_id.clone();
+        let refreshing = self.refreshing.clone();
+        tokio::spawn(async move {
+            data_source.expire();
+            match Snapshot::load(data_source.as_ref(), &manager_id, None).await {
+                Ok(snapshot) => {
+                    let _ = event_tx.send(Event::Refreshed(Box::new(snapshot)));
+                },
+                Err(e) => {
+                    let _ = action_tx.send(Action::failed(Action::Refresh, format!("Refresh failed: {}", e)));
+                },
+            }
+            refreshing.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Put another manager's team in Home; the result arrives as [`Event::Refreshed`].
+    fn view_manager(
+        &mut self,
+        manager_id: String,
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        self.manager_id = manager_id.clone();
+        action_tx.send(Action::SwitchMode(Mode::Home))?;
+        let data_source = self.data_source.clone();
+        tokio::spawn(async move {
+            match Snapshot::load(data_source.as_ref(), &manager_id, None).await {
+                Ok(snapshot) => {
+                    let _ = event_tx.send(Event::Refreshed(Box::new(snapshot)));
+                },
+                Err(e) => {
+                    let _ = action_tx.send(Action::Error(format!("Unable to load team {}: {}", manager_id, e)));
+                },
+            }
+        });
+        Ok(())
+    }
+
+    /// Send the action bound to the keys pressed since the last tick, in the current mode or globally.
+    fn handle_key_event(&mut self, key: KeyEvent, action_tx: &UnboundedSender<Action>) -> Result<()> {
+        self.last_tick_key_events.push(key);
+        if let Some(action) = self.config.keybindings.action(self.mode, &self.last_tick_key_events) {
+            action_tx.send(action.clone())?;
+            self.last_tick_key_events.clear();
+        }
+        Ok(())
+    }
+
+    /// Make the next profile in the config the manager's own and open their team.
+    fn next_profile(&mut self, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) -> Result<()> {
+        let current = self.profile.as_ref().map(|(name, _)| name.as_str());
+        let Some((name, profile)) = self.config.config.next_profile(current) else {
+            action_tx.send(Action::Error("No profiles in the config to switch between".to_string()))?;
+            return Ok(());
+        };
+        self.own_manager_id = profile.manager_id.to_string();
+        event_tx.send(Event::ProfileSwitched(name.clone(), profile.clone()))?;
+        self.profile = Some((name, profile));
+        self.view_manager(self.own_manager_id.clone(), event_tx, action_tx)
+    }
+
+    /// Fetch a gameweek's picks and points; the result arrives as [`Event::GameweekLoaded`].
+    fn load_gameweek(&self, gameweek: i64, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) {
+        let data_source = self.data_source.clone();
+        let manager_id = self.manager_id.clone();
+        tokio::spawn(async move {
+            let (gw_team, live) = futures::join!(
+                data_source.get_manager_team_for_gw(&manager_id, gameweek),
+                data_source.get_gw_live_data(gameweek),
+            );
+            match gw_team {
+                Ok(gw_team) => {
+                    if let Err(e) = &live {
+                        log::warn!("No live points for GW{}: {}", gameweek, e);
+                    }
+                    let _ = event_tx.send(Event::GameweekLoaded(gameweek, Box::new(gw_team), live.ok()));
+                },
+                Err(e) => {
+                    let request = Action::LoadGameweek(gameweek);
+                    let _ = action_tx.send(Action::failed(request, format!("Unable to load GW{}: {}", gameweek, e)));
+                },
+            }
+        });
+    }
+
+    fn load_history(&self, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) {
+        let data_source = self.data_source.clone();
+        let manager_id = self.manager_id.clone();
+        tokio::spawn(async move {
+            match data_source.get_manager_history(&manager_id).await {
+                Ok(history) => {
+                    let id = manager_id.parse().unwrap_or_default();
+                    let _ = event_tx.send(Event::ManagerHistory(id, Box::new(history)));
+                },
+                Err(e) => {
+                    let _ = action_tx
+                        .send(Action::failed(Action::LoadHistory, format!("Unable to load season history: {}", e)));
+                },
+            }
+        });
+    }
+
+    fn load_classic_standings(
+        &self,
+        league_id: i64,
+        page: i64,
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let data_source = self.data_source.clone();
+        tokio::spawn(async move {
+            match data_source.get_classic_standings(league_id, page).await {
+                Ok(standings) => {
+                    let _ = event_tx.send(Event::ClassicStandings(page, Box::new(standings)));
+                },
+                Err(e) => {
+                    let request = Action::LoadClassicStandings(league_id, page);
+                    let _ =
+                        action_tx.send(Action::failed(request, format!("Unable to load league {}: {}", league_id, e)));
+                },
+            }
+        });
+    }
+
+    fn load_h2h_standings(
+        &self,
+        league_id: i64,
+        page: i64,
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let data_source = self.data_source.clone();
+        tokio::spawn(async move {
+            match data_source.get_h2h_standings(league_id, page).await {
+                Ok(standings) => {
+                    let _ = event_tx.send(Event::H2HStandings(page, Box::new(standings)));
+                },
+                Err(e) => {
+                    let request = Action::LoadH2HStandings(league_id, page);
+                    let _ =
+                        action_tx.send(Action::failed(request, format!("Unable to load league {}: {}", league_id, e)));
+                },
+            }
+        });
+    }
+
+    fn load_h2h_matches(
+        &self,
+        league_id: i64,
+        gameweek: i64,
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let data_source = self.data_source.clone();
+        tokio::spawn(async move {
+            match data_source.get_h2h_matches(league_id, gameweek).await {
+                Ok(matches) => {
+                    let _ = event_tx.send(Event::H2HMatches(league_id, gameweek, Box::new(matches)));
+                },
+                Err(e) => {
+                    let request = Action::LoadH2HMatches(league_id, gameweek);
+                    let _ = action_tx.send(Action::failed(
+                        request,
+                        format!("Unable to load GW{} matches for league {}: {}", gameweek, league_id, e),
+                    ));
+                },
+            }
+        });
+    }
+
+    /// Fetch both squads of an H2H matchup; the result arrives as [`Event::MatchupLoaded`].
+    fn load_matchup(
+        &self,
+        gameweek: i64,
+        entries: [i64; 2],
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let data_source = self.data_source.clone();
+        tokio::spawn(async move {
+            let (ids, live) = (entries.map(|e| e.to_string()), data_source.get_gw_live_data(gameweek));
+            let (team_1, team_2, live) = futures::join!(
+                data_source.get_manager_team_for_gw(&ids[0], gameweek),
+                data_source.get_manager_team_for_gw(&ids[1], gameweek),
+                live,
+            );
+            match (team_1, team_2) {
+                (Ok(team_1), Ok(team_2)) => {
+                    if let Err(e) = &live {
+                        log::warn!("No live points for GW{}: {}", gameweek, e);
+                    }
+                    let _ =
+                        event_tx.send(Event::MatchupLoaded(gameweek, entries, Box::new([team_1, team_2]), live.ok()));
+                },
+                (Err(e), _) | (_, Err(e)) => {
+                    let request = Action::LoadMatchup(gameweek, entries[0], entries[1]);
+                    let _ = action_tx
+                        .send(Action::failed(request, format!("Unable to load GW{} matchup: {}", gameweek, e)));
+                },
+            }
+        });
+    }
+
+    fn fetch_live_data(&self, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) {
+        let data_source = self.data_source.clone();
+        let gameweek = self.gameweek;
+        tokio::spawn(async move {
+            match data_source.get_gw_live_data(gameweek).await {
+                Ok(live) => {
+                    let _ = event_tx.send(Event::LiveData(gameweek, live));
+                },
+                Err(e) => {
+                    let _ = action_tx.send(Action::Error(format!("Unable to fetch live points: {}", e)));
+                },
+            }
+        });
+    }
+
     async fn load_team_images() -> Result<HashMap<i64, DynamicImage>> {
         let mut ti = HashMap::new();
         for i in 1..100 {
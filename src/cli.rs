@@ -1,8 +1,8 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use crate::utils::version;
+use crate::{commands::status, utils::version};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -25,6 +25,71 @@ pub struct Cli {
     )]
     pub frame_rate: f64,
 
-    #[arg(short, long, value_name = "ID", help = "Manager Id")]
-    pub manager_id: String,
+    #[arg(short, long, global = true, value_name = "ID", help = "Manager Id")]
+    pub manager_id: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "NAME",
+        conflicts_with = "manager_id",
+        help = "Profile from the config file, defaults to default_profile when no manager id is given"
+    )]
+    pub profile: Option<String>,
+
+    #[arg(short, long, global = true, value_name = "GW", help = "Gameweek to open on, defaults to the current one")]
+    pub gameweek: Option<i64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Read recorded API responses from this directory instead of the FPL API"
+    )]
+    pub data_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Run without network access, reading recorded responses from the data directory"
+    )]
+    pub offline: bool,
+
+    #[arg(long, global = true, help = "Print JSON instead of a table")]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Print something and exit instead of starting the TUI.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// The manager's picks and points for the gameweek
+    Team,
+    /// The gameweek's fixtures, defaulting to the next one
+    Fixtures,
+    /// A player's numbers and upcoming fixtures, found by name
+    Player {
+        #[arg(required = true, num_args = 1..)]
+        name: Vec<String>,
+    },
+    /// A league's standings
+    League {
+        id: i64,
+        #[arg(long, help = "Read the league as head-to-head rather than classic")]
+        h2h: bool,
+        #[arg(long, default_value_t = 1, help = "Page of the standings, 50 managers to a page")]
+        page: i64,
+    },
+    /// A one-line summary of the manager's gameweek, for status bars
+    Status {
+        #[arg(long, default_value = status::DEFAULT_FORMAT, help = status::FORMAT_HELP)]
+        format: String,
+        #[arg(long, help = "Keep printing a fresh line until stopped")]
+        watch: bool,
+        #[arg(long, value_name = "SECS", help = "Seconds between lines with --watch, defaults to live_refresh_secs")]
+        interval: Option<u64>,
+    },
 }
@@ -0,0 +1,553 @@
+//! Commands that print FPL data and exit, for scripts, cron jobs and bots.
+//!
+//! Each one loads through the same [`DataSource`] as the TUI and prints a plain text table, or
+//! with `--json` the same data as JSON.
+
+use std::collections::HashMap;
+
+use color_eyre::eyre::{eyre, OptionExt, Result};
+use fpl_api::{
+    bootstrap::{BootstrapData, Element},
+    manager::Pick,
+    players::Element as LiveElement,
+};
+use serde::Serialize;
+use thousands::Separable;
+
+use crate::{
+    api::{ClassicStandings, H2HStandings},
+    cli::{Cli, Command},
+    config::Config,
+    data_source::{DataSource, Snapshot},
+    fdr, fuzzy, lineup, points,
+    projection::Projection,
+    utils::format_kickoff,
+};
+
+pub mod status;
+
+const POSITIONS: [&str; 5] = ["", "GK", "DEF", "MID", "FWD"];
+/// Other players listed under a `player` lookup, in case the best match is the wrong one.
+const OTHER_MATCHES: usize = 4;
+
+/// What a command prints.
+trait Output: Serialize {
+    /// The output for people, ending in a newline.
+    fn table(&self) -> String;
+}
+
+pub async fn run(command: &Command, args: &Cli, config: &Config, data_source: &dyn DataSource) -> Result<()> {
+    match command {
+        Command::Team => {
+            let manager_id = args
+                .manager_id
+                .as_deref()
+                .ok_or_eyre("tfpl team needs a manager id, pass one with --manager-id or --profile")?;
+            print(&team(data_source, manager_id, args.gameweek).await?, args.json)
+        },
+        Command::Fixtures => print(&fixtures(data_source, args.gameweek).await?, args.json),
+        Command::Player { name } => {
+            let horizon = config.config.projection_gameweeks;
+            print(&player(data_source, &name.join(" "), horizon).await?, args.json)
+        },
+        Command::League { id, h2h: false, page } => {
+            print(&data_source.get_classic_standings(*id, *page).await?, args.json)
+        },
+        Command::League { id, h2h: true, page } => print(&data_source.get_h2h_standings(*id, *page).await?, args.json),
+        Command::Status { format, watch, interval } => {
+            let manager_id = args
+                .manager_id
+                .as_deref()
+                .ok_or_eyre("tfpl status needs a manager id, pass one with --manager-id or --profile")?;
+            let interval = interval.unwrap_or(config.config.live_refresh_secs);
+            status::run(data_source, manager_id, format, *watch, interval, args.json).await
+        },
+    }
+}
+
+fn print(output: &impl Output, json: bool) -> Result<()> {
+    match json {
+        true => println!("{}", serde_json::to_string_pretty(output)?),
+        false => print!("{}", output.table()),
+    }
+    Ok(())
+}
+
+/// Whether a cell is a number, allowing for signs, thousands separators and units like `£5.5m` or `12%`.
+fn is_number(s: &str) -> bool {
+    s.trim_start_matches(['£', '+', '-']).trim_end_matches(['m', '%']).replace(',', "").parse::<f64>().is_ok()
+}
+
+/// Columns padded to their widest cell, with columns of numbers right-aligned.
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let cell = |row: &[String], c: usize| row.get(c).map_or("", |s| s.as_str()).to_string();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|c| rows.iter().map(|r| cell(r, c).chars().count()).chain([header[c].chars().count()]).max().unwrap_or(0))
+        .collect();
+    let numeric: Vec<bool> = (0..header.len())
+        .map(|c| {
+            let cells: Vec<String> = rows.iter().map(|r| cell(r, c)).filter(|s| !s.is_empty()).collect();
+            !cells.is_empty() && cells.iter().all(|s| is_number(s))
+        })
+        .collect();
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(c, s)| {
+                match numeric[c] {
+                    true => format!("{:>width$}", s, width = widths[c]),
+                    false => format!("{:<width$}", s, width = widths[c]),
+                }
+            })
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    let mut out = line(header.iter().map(|h| h.to_string()).collect());
+    for row in rows {
+        out += &line((0..header.len()).map(|c| cell(row, c)).collect());
+    }
+    out
+}
+
+fn club(bootstrap: &BootstrapData, team: i64) -> String {
+    bootstrap.teams.iter().find(|t| t.id == team).map_or_else(String::new, |t| t.short_name.clone())
+}
+
+fn position(element_type: i64) -> String {
+    POSITIONS.get(element_type as usize).copied().unwrap_or("").to_string()
+}
+
+/// The gameweek's live stats by element id, or nothing if they can't be had.
+async fn live_stats(data_source: &dyn DataSource, gameweek: i64) -> Option<HashMap<i64, LiveElement>> {
+    match data_source.get_gw_live_data(gameweek).await {
+        Ok(live) => Some(points::index_live(&live)),
+        Err(e) => {
+            log::warn!("No live points for GW{}: {}", gameweek, e);
+            None
+        },
+    }
+}
+
+/// The picks as they score, after automatic substitutions and any change of armband.
+fn effective_picks(snapshot: &Snapshot, live: &HashMap<i64, LiveElement>) -> Vec<Pick> {
+    let elements: HashMap<i64, Element> = snapshot.bootstrap.elements.iter().map(|e| (e.id, e.clone())).collect();
+    let gameweek = snapshot.gw_picks.entry_history.event;
+    points::live_effective_picks(&snapshot.gw_picks.picks, gameweek, &elements, &snapshot.fixtures, live)
+}
+
+/// A manager's picks and points for a gameweek.
+#[derive(Debug, Serialize)]
+struct TeamSheet {
+    manager_id: i64,
+    manager: String,
+    team_name: String,
+    gameweek: i64,
+    /// Points after automatic substitutions and before transfer hits.
+    points: i64,
+    transfer_cost: i64,
+    total_points: i64,
+    overall_rank: i64,
+    picks: Vec<SheetPick>,
+}
+
+#[derive(Debug, Serialize)]
+struct SheetPick {
+    element: i64,
+    name: String,
+    club: String,
+    position: String,
+    starter: bool,
+    captain: bool,
+    vice_captain: bool,
+    multiplier: i64,
+    /// Points before the multiplier, when there are live stats for the gameweek.
+    points: Option<i64>,
+}
+
+async fn team(data_source: &dyn DataSource, manager_id: &str, gameweek: Option<i64>) -> Result<TeamSheet> {
+    let snapshot = Snapshot::load(data_source, manager_id, gameweek).await?;
+    let gameweek = snapshot.gw_picks.entry_history.event;
+    let live = live_stats(data_source, gameweek).await;
+    let elements: HashMap<i64, &Element> = snapshot.bootstrap.elements.iter().map(|e| (e.id, e)).collect();
+    let element_type = |element: i64| elements.get(&element).map_or(0, |e| e.element_type);
+    let mut picks = match &live {
+        Some(live) => effective_picks(&snapshot, live),
+        None => snapshot.gw_picks.picks.clone(),
+    };
+    picks.sort_by_key(|p| p.position);
+    let points_for = |element: i64| live.as_ref().and_then(|live| live.get(&element)).map(|e| e.stats.total_points);
+    let history = &snapshot.gw_picks.entry_history;
+    Ok(TeamSheet {
+        manager_id: snapshot.manager.id,
+        manager: format!("{} {}", snapshot.manager.player_first_name, snapshot.manager.player_last_name),
+        team_name: snapshot.manager.name.clone(),
+        gameweek,
+        points: match live {
+            Some(_) => points::gw_total(&picks, |element| points_for(element).unwrap_or(0)),
+            None => history.points,
+        },
+        transfer_cost: history.event_transfers_cost,
+        total_points: history.total_points,
+        overall_rank: history.overall_rank,
+        picks: picks
+            .iter()
+            .map(|p| {
+                let element = elements.get(&p.element);
+                SheetPick {
+                    element: p.element,
+                    name: element.map_or_else(|| p.element.to_string(), |e| e.web_name.clone()),
+                    club: element.map_or_else(String::new, |e| club(&snapshot.bootstrap, e.team)),
+                    position: position(element_type(p.element)),
+                    starter: lineup::is_starter(p),
+                    captain: p.is_captain,
+                    vice_captain: p.is_vice_captain,
+                    multiplier: p.multiplier,
+                    points: points_for(p.element),
+                }
+            })
+            .collect(),
+    })
+}
+
+impl Output for TeamSheet {
+    fn table(&self) -> String {
+        let hit = match self.transfer_cost {
+            0 => String::new(),
+            cost => format!(" (-{})", cost),
+        };
+        let mut out = format!(
+            "{} · {} · GW{}: {} pts{} · {} total · rank {}\n\n",
+            self.manager,
+            self.team_name,
+            self.gameweek,
+            self.points,
+            hit,
+            self.total_points.separate_with_commas(),
+            self.overall_rank.separate_with_commas(),
+        );
+        let rows: Vec<Vec<String>> = self
+            .picks
+            .iter()
+            .map(|p| {
+                let role = match (p.starter, p.captain, p.vice_captain, p.multiplier) {
+                    (false, ..) => "Bench",
+                    (_, true, _, 3) => "TC",
+                    (_, true, _, 2) => "C",
+                    (_, _, true, m) if m > 1 => "V→C",
+                    (_, true, ..) => "C",
+                    (_, _, true, _) => "V",
+                    _ => "",
+                };
+                vec![
+                    p.position.clone(),
+                    p.name.clone(),
+                    p.club.clone(),
+                    role.to_string(),
+                    p.points.map_or_else(String::new, |points| points.to_string()),
+                ]
+            })
+            .collect();
+        out += &table(&["Pos", "Player", "Club", "", "Pts"], &rows);
+        out
+    }
+}
+
+/// A gameweek's fixtures.
+#[derive(Debug, Serialize)]
+struct GameweekFixtures {
+    gameweek: i64,
+    fixtures: Vec<FixtureRow>,
+}
+
+#[derive(Debug, Serialize)]
+struct FixtureRow {
+    kickoff_time: String,
+    home: String,
+    away: String,
+    home_score: Option<i64>,
+    away_score: Option<i64>,
+    home_difficulty: i64,
+    away_difficulty: i64,
+    started: bool,
+    finished: bool,
+}
+
+async fn fixtures(data_source: &dyn DataSource, gameweek: Option<i64>) -> Result<GameweekFixtures> {
+    let (bootstrap, fixtures) = futures::try_join!(data_source.get_bootstrap_data(), data_source.get_fixtures())?;
+    let gameweek = gameweek
+        .or_else(|| bootstrap.events.iter().find(|e| e.is_next).map(|e| e.id))
+        .or_else(|| bootstrap.events.iter().find(|e| e.is_current).map(|e| e.id))
+        .ok_or_eyre("The season is over, pass a gameweek with --gameweek")?;
+    let mut fixtures: Vec<_> = fixtures.into_iter().filter(|f| f.event == gameweek).collect();
+    fixtures.sort_by(|a, b| a.kickoff_time.cmp(&b.kickoff_time).then(a.id.cmp(&b.id)));
+    Ok(GameweekFixtures {
+        gameweek,
+        fixtures: fixtures
+            .into_iter()
+            .map(|f| {
+                FixtureRow {
+                    home: club(&bootstrap, f.team_h),
+                    away: club(&bootstrap, f.team_a),
+                    home_score: f.team_h_score,
+                    away_score: f.team_a_score,
+                    home_difficulty: f.team_h_difficulty,
+                    away_difficulty: f.team_a_difficulty,
+                    started: f.started,
+                    finished: f.finished || f.finished_provisional,
+                    kickoff_time: f.kickoff_time,
+                }
+            })
+            .collect(),
+    })
+}
+
+impl Output for GameweekFixtures {
+    fn table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .fixtures
+            .iter()
+            .map(|f| {
+                let score = match (f.home_score, f.away_score) {
+                    (Some(home), Some(away)) => format!("{} - {}", home, away),
+                    _ => "v".to_string(),
+                };
+                vec![
+                    format_kickoff(&f.kickoff_time),
+                    f.home.clone(),
+                    f.home_difficulty.to_string(),
+                    score,
+                    f.away_difficulty.to_string(),
+                    f.away.clone(),
+                ]
+            })
+            .collect();
+        format!("GW{}\n\n{}", self.gameweek, table(&["Kickoff", "Home", "FDR", "Score", "FDR", "Away"], &rows))
+    }
+}
+
+/// Players matching `query`, best first.
+fn search<'a>(elements: &'a [Element], query: &str) -> Vec<&'a Element> {
+    let mut matches: Vec<(i64, &Element)> = elements
+        .iter()
+        .filter_map(|e| {
+            let full = fuzzy::score(query, &format!("{} {}", e.first_name, e.second_name));
+            let score = full.max(fuzzy::score(query, &e.web_name))?;
+            Some((score, e))
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_points.cmp(&a.1.total_points)));
+    matches.into_iter().map(|(_, e)| e).collect()
+}
+
+/// A player's numbers and their projected points over the coming gameweeks.
+#[derive(Debug, Serialize)]
+struct PlayerReport {
+    id: i64,
+    name: String,
+    web_name: String,
+    club: String,
+    position: String,
+    /// In millions.
+    price: f64,
+    form: String,
+    points_per_game: String,
+    total_points: i64,
+    event_points: i64,
+    selected_by_percent: String,
+    status: String,
+    news: String,
+    expected_points: f64,
+    upcoming: Vec<UpcomingGameweek>,
+    other_matches: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct UpcomingGameweek {
+    gameweek: i64,
+    /// Opponents with `(H)` or `(A)`, none for a blank and two for a double.
+    opponents: Vec<String>,
+    expected_points: f64,
+}
+
+async fn player(data_source: &dyn DataSource, query: &str, horizon: i64) -> Result<PlayerReport> {
+    let (bootstrap, fixtures) = futures::try_join!(data_source.get_bootstrap_data(), data_source.get_fixtures())?;
+    let matches = search(&bootstrap.elements, query);
+    let e = *matches.first().ok_or_else(|| eyre!("No player matches \"{}\"", query))?;
+    let projection = Projection::new(&bootstrap, &fixtures, horizon);
+    let upcoming = projection.upcoming(e.id);
+    let schedule = fdr::schedule(&fixtures, projection.first, upcoming.len() as i64).remove(&e.team);
+    Ok(PlayerReport {
+        id: e.id,
+        name: format!("{} {}", e.first_name, e.second_name),
+        web_name: e.web_name.clone(),
+        club: club(&bootstrap, e.team),
+        position: position(e.element_type),
+        price: e.now_cost as f64 / 10.0,
+        form: e.form.clone(),
+        points_per_game: e.points_per_game.clone(),
+        total_points: e.total_points,
+        event_points: e.event_points,
+        selected_by_percent: e.selected_by_percent.clone(),
+        status: e.status.clone(),
+        news: e.news.clone(),
+        expected_points: projection.expected(e.id),
+        upcoming: upcoming
+            .iter()
+            .enumerate()
+            .map(|(i, &(gameweek, expected_points))| {
+                let opponents = schedule.as_ref().and_then(|s| s.get(i)).map_or(Vec::new(), |fixtures| {
+                    fixtures
+                        .iter()
+                        .map(|f| format!("{} ({})", club(&bootstrap, f.opponent), if f.is_home { "H" } else { "A" }))
+                        .collect()
+                });
+                UpcomingGameweek { gameweek, opponents, expected_points }
+            })
+            .collect(),
+        other_matches: matches
+            .iter()
+            .skip(1)
+            .take(OTHER_MATCHES)
+            .map(|m| format!("{} ({})", m.web_name, club(&bootstrap, m.team)))
+            .collect(),
+    })
+}
+
+impl Output for PlayerReport {
+    fn table(&self) -> String {
+        let mut out = format!("{} · {} · {}\n", self.name, self.club, self.position);
+        out += &format!(
+            "£{:.1}m · form {} · {} pts/game · {} pts · {}% owned\n",
+            self.price, self.form, self.points_per_game, self.total_points, self.selected_by_percent
+        );
+        if !self.news.is_empty() {
+            out += &format!("{}\n", self.news);
+        }
+        out += &format!("xP over the next {} GWs: {:.1}\n\n", self.upcoming.len(), self.expected_points);
+        let rows: Vec<Vec<String>> = self
+            .upcoming
+            .iter()
+            .map(|gw| {
+                let opponents = match gw.opponents.is_empty() {
+                    true => "-".to_string(),
+                    false => gw.opponents.join(", "),
+                };
+                vec![gw.gameweek.to_string(), opponents, format!("{:.1}", gw.expected_points)]
+            })
+            .collect();
+        out += &table(&["GW", "Opponents", "xP"], &rows);
+        if !self.other_matches.is_empty() {
+            out += &format!("\nAlso matching: {}\n", self.other_matches.join(", "));
+        }
+        out
+    }
+}
+
+fn more_pages(has_next: bool, page: i64) -> String {
+    match has_next {
+        true => format!("\nMore with --page {}\n", page + 1),
+        false => String::new(),
+    }
+}
+
+impl Output for ClassicStandings {
+    fn table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .standings
+            .results
+            .iter()
+            .map(|e| {
+                let movement = match (e.last_rank, e.last_rank - e.rank) {
+                    (0, _) | (_, 0) => String::new(),
+                    (_, change) => format!("{:+}", change),
+                };
+                vec![
+                    e.rank.to_string(),
+                    movement,
+                    e.player_name.clone(),
+                    e.entry_name.clone(),
+                    e.event_total.to_string(),
+                    e.total.separate_with_commas(),
+                ]
+            })
+            .collect();
+        format!(
+            "{}\n\n{}{}",
+            self.league.name,
+            table(&["Rank", "±", "Manager", "Team", "GW", "Total"], &rows),
+            more_pages(self.standings.has_next, self.standings.page)
+        )
+    }
+}
+
+impl Output for H2HStandings {
+    fn table(&self) -> String {
+        let rows: Vec<Vec<String>> = self
+            .standings
+            .results
+            .iter()
+            .map(|e| {
+                vec![
+                    e.rank.to_string(),
+                    e.player_name.clone(),
+                    e.entry_name.clone(),
+                    e.matches_won.to_string(),
+                    e.matches_drawn.to_string(),
+                    e.matches_lost.to_string(),
+                    e.points_for.separate_with_commas(),
+                    e.total.to_string(),
+                ]
+            })
+            .collect();
+        format!(
+            "{}\n\n{}{}",
+            self.league.name,
+            table(&["Rank", "Manager", "Team", "W", "D", "L", "For", "Pts"], &rows),
+            more_pages(self.standings.has_next, self.standings.page)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_table_pads_and_right_aligns_numbers() {
+        let rows = vec![vec!["Salah".to_string(), "£13.0m".to_string(), "120".to_string()], vec![
+            "Ødegaard".to_string(),
+            "£8.5m".to_string(),
+            String::new(),
+        ]];
+        let expected = "\
+Player     Price  Pts
+Salah     £13.0m  120
+Ødegaard   £8.5m
+";
+        assert_eq!(table(&["Player", "Price", "Pts"], &rows), expected);
+    }
+
+    #[test]
+    fn test_search_prefers_the_closest_name() {
+        let player = |id, first: &str, second: &str, web: &str| {
+            Element {
+                id,
+                first_name: first.to_string(),
+                second_name: second.to_string(),
+                web_name: web.to_string(),
+                ..Default::default()
+            }
+        };
+        let elements = vec![
+            player(1, "Sam", "Lavia", "Lavia"),
+            player(2, "Mohamed", "Salah", "M.Salah"),
+            player(3, "Bruno", "Borges Fernandes", "B.Fernandes"),
+        ];
+        let ids = |query| search(&elements, query).iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids("sala"), vec![2, 1]);
+        assert_eq!(ids("b.fernandes"), vec![3]);
+    }
+}
@@ -0,0 +1,212 @@
+//! `tfpl status`: the manager's gameweek on one line, for tmux, waybar and the like.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Serialize;
+
+use super::{effective_picks, live_stats};
+use crate::{
+    data_source::{DataSource, Snapshot},
+    points,
+};
+
+pub const DEFAULT_FORMAT: &str = "GW{gw} {points}pts{live} rank {rank_change} | deadline {deadline}";
+pub const FORMAT_HELP: &str = "Template for the line, using any of {gw} {points} {live} {hit} {total} {rank} \
+                               {rank_change} {gw_rank} {captain} {team} {deadline}";
+
+/// Everything a status line can show.
+#[derive(Default, Debug, Serialize)]
+struct Status {
+    gameweek: i64,
+    /// Points after automatic substitutions and before transfer hits.
+    points: i64,
+    /// Whether the gameweek is still being played.
+    live: bool,
+    transfer_cost: i64,
+    total_points: i64,
+    overall_rank: i64,
+    /// Places gained overall since the previous gameweek, negative when places were lost.
+    rank_change: Option<i64>,
+    gw_rank: Option<i64>,
+    captain: Option<String>,
+    team_name: String,
+    /// Unix time of the next transfer deadline.
+    next_deadline: Option<i64>,
+    seconds_to_deadline: Option<i64>,
+}
+
+impl Status {
+    fn values(&self) -> Vec<(&'static str, String)> {
+        let rank_change = match self.rank_change {
+            Some(change) if change > 0 => format!("↑ {}", compact(change)),
+            Some(change) if change < 0 => format!("↓ {}", compact(change)),
+            Some(_) => "=".to_string(),
+            None => "-".to_string(),
+        };
+        vec![
+            ("gw", self.gameweek.to_string()),
+            ("points", self.points.to_string()),
+            ("live", if self.live { " (live)".to_string() } else { String::new() }),
+            ("hit", if self.transfer_cost > 0 { format!("-{}", self.transfer_cost) } else { String::new() }),
+            ("total", self.total_points.to_string()),
+            ("rank", compact(self.overall_rank)),
+            ("rank_change", rank_change),
+            ("gw_rank", self.gw_rank.map_or_else(|| "-".to_string(), compact)),
+            ("captain", self.captain.clone().unwrap_or_else(|| "-".to_string())),
+            ("team", self.team_name.clone()),
+            ("deadline", self.seconds_to_deadline.map_or_else(|| "-".to_string(), countdown)),
+        ]
+    }
+}
+
+/// A rank or rank change as `950`, `4.5k`, `120k` or `1.2M`, ignoring the sign.
+fn compact(n: i64) -> String {
+    match n.abs() {
+        n if n < 1_000 => n.to_string(),
+        n if n < 10_000 => format!("{:.1}k", n as f64 / 1_000.0),
+        n if n < 1_000_000 => format!("{:.0}k", n as f64 / 1_000.0),
+        n => format!("{:.1}M", n as f64 / 1_000_000.0),
+    }
+}
+
+/// Time left as `2d 3h`, `3h 20m` or `20m`.
+fn countdown(secs: i64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+/// Fill in the `{placeholder}`s in `format`, failing on one that doesn't exist.
+fn render(format: &str, values: &[(&str, String)]) -> Result<String> {
+    let mut line = String::new();
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        let end = start + rest[start..].find('}').ok_or_else(|| eyre!("Unclosed {{ in the status format"))?;
+        let name = &rest[start + 1..end];
+        let (_, value) = values
+            .iter()
+            .find(|(placeholder, _)| *placeholder == name)
+            .ok_or_else(|| eyre!("Unknown placeholder {{{}}} in the status format. {}", name, FORMAT_HELP))?;
+        line.push_str(value);
+        rest = &rest[end + 1..];
+    }
+    line.push_str(rest);
+    Ok(line)
+}
+
+async fn status(data_source: &dyn DataSource, manager_id: &str) -> Result<Status> {
+    let snapshot = Snapshot::load(data_source, manager_id, None).await?;
+    let entry = &snapshot.gw_picks.entry_history;
+    let (live, history) =
+        futures::join!(live_stats(data_source, entry.event), data_source.get_manager_history(manager_id));
+    let picks = match &live {
+        Some(live) => effective_picks(&snapshot, live),
+        None => snapshot.gw_picks.picks.clone(),
+    };
+    let points = match &live {
+        Some(live) => points::gw_total(&picks, |element| live.get(&element).map_or(0, |e| e.stats.total_points)),
+        None => entry.points,
+    };
+    let rank_change = match history {
+        Ok(history) => {
+            history
+                .current
+                .iter()
+                .rev()
+                .find(|gw| gw.event < entry.event)
+                .map(|gw| gw.overall_rank - entry.overall_rank)
+        },
+        Err(e) => {
+            log::warn!("No season history for the rank change: {}", e);
+            None
+        },
+    };
+    let armband = picks
+        .iter()
+        .find(|p| p.multiplier > 1 && (p.is_captain || p.is_vice_captain))
+        .or_else(|| picks.iter().find(|p| p.is_captain));
+    let captain = armband
+        .and_then(|p| snapshot.bootstrap.elements.iter().find(|e| e.id == p.element))
+        .map(|e| e.web_name.clone());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+    let next_deadline = snapshot.bootstrap.events.iter().map(|e| e.deadline_time_epoch).filter(|t| *t > now).min();
+    Ok(Status {
+        gameweek: entry.event,
+        points,
+        live: snapshot.bootstrap.events.iter().any(|e| e.id == entry.event && e.is_current && !e.finished),
+        transfer_cost: entry.event_transfers_cost,
+        total_points: entry.total_points,
+        overall_rank: entry.overall_rank,
+        rank_change,
+        gw_rank: entry.rank.or(snapshot.manager.summary_event_rank),
+        captain,
+        team_name: snapshot.manager.name.clone(),
+        next_deadline,
+        seconds_to_deadline: next_deadline.map(|t| t - now),
+    })
+}
+
+/// Print the status line, and with `watch` keep printing one every `interval` seconds.
+///
+/// While watching, a failed update is reported on stderr and the next one tried as usual. Each
+/// update goes through the cache, so endpoints are only fetched again once their TTLs run out:
+/// the live points every half a minute by default, the bootstrap far less often.
+pub async fn run(
+    data_source: &dyn DataSource,
+    manager_id: &str,
+    format: &str,
+    watch: bool,
+    interval: u64,
+    json: bool,
+) -> Result<()> {
+    // A typo in the template should fail straight away rather than after the first fetch
+    render(format, &Status::default().values())?;
+    loop {
+        match status(data_source, manager_id).await {
+            Ok(status) if json => println!("{}", serde_json::to_string(&status)?),
+            Ok(status) => println!("{}", render(format, &status.values())?),
+            Err(e) if watch => eprintln!("{}", e),
+            Err(e) => return Err(e),
+        }
+        if !watch {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(interval.max(1))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_default_format() {
+        let status = Status {
+            gameweek: 12,
+            points: 54,
+            live: true,
+            rank_change: Some(120_345),
+            seconds_to_deadline: Some(2 * 86_400 + 3 * 3_600 + 59),
+            ..Default::default()
+        };
+        assert_eq!(render(DEFAULT_FORMAT, &status.values()).unwrap(), "GW12 54pts (live) rank ↑ 120k | deadline 2d 3h");
+        assert_eq!(compact(-4_520), "4.5k");
+        assert_eq!(compact(1_234_567), "1.2M");
+        assert_eq!(countdown(3 * 3_600 + 20 * 60), "3h 20m");
+    }
+
+    #[test]
+    fn test_unknown_placeholders_fail() {
+        let values = Status::default().values();
+        assert!(render("{gw} {pts}", &values).is_err());
+        assert!(render("{gw", &values).is_err());
+        assert_eq!(render("plain", &values).unwrap(), "plain");
+    }
+}
@@ -5,11 +5,20 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{action::Action, config::Config, event::Event, tui::Frame};
 
+pub mod browser;
+pub mod fixtures;
 pub mod fps;
+mod h2h;
+pub mod help;
+pub mod history;
 pub mod home;
+pub mod leagues;
 mod manager_summary;
+pub mod planner;
 mod player_card;
 pub mod players;
+mod price_chart;
+pub mod prices;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 /// Implementors of this trait can be registered with the main application loop and will be able to receive events,
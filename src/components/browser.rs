@@ -0,0 +1,564 @@
+use std::{collections::HashMap, sync::Arc};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use fpl_api::{
+    bootstrap::{Element, Team},
+    manager::Pick,
+};
+use image::DynamicImage;
+use ratatui::{prelude::*, widgets::*};
+use ratatui_image::picker::Picker;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{player_card::PlayerCard, price_chart, Component, Frame};
+use crate::{
+    action::Action,
+    config::Config,
+    data_source::Snapshot,
+    event::Event,
+    fuzzy,
+    mode::Mode,
+    price_history::{self, PriceHistory},
+    projection::Projection,
+};
+
+/// A numeric column the table can be sorted by.
+struct Stat {
+    header: &'static str,
+    width: u16,
+    value: fn(&Element, &PlayerBrowser) -> f64,
+    format: fn(&Element, &PlayerBrowser) -> String,
+}
+
+fn parse(s: &str) -> f64 {
+    s.parse().unwrap_or(0.0)
+}
+
+const STATS: &[Stat] = &[
+    Stat {
+        header: "£",
+        width: 5,
+        value: |e, _| e.now_cost as f64,
+        format: |e, _| format!("{:.1}", e.now_cost as f64 / 10.0),
+    },
+    Stat {
+        header: "±Run",
+        width: 5,
+        value: |e, b| b.prices.since_last_run(e.id, e.now_cost).unwrap_or(0) as f64,
+        format: |e, b| price_history::format_change(b.prices.since_last_run(e.id, e.now_cost).unwrap_or(0)),
+    },
+    Stat {
+        header: "±GW",
+        width: 5,
+        value: |e, _| e.cost_change_event as f64,
+        format: |e, _| price_history::format_change(e.cost_change_event),
+    },
+    Stat {
+        header: "xP",
+        width: 5,
+        value: |e, b| b.projection.expected(e.id),
+        format: |e, b| format!("{:.1}", b.projection.expected(e.id)),
+    },
+    Stat { header: "Pts", width: 4, value: |e, _| e.total_points as f64, format: |e, _| e.total_points.to_string() },
+    Stat { header: "Form", width: 5, value: |e, _| parse(&e.form), format: |e, _| e.form.clone() },
+    Stat { header: "PPG", width: 4, value: |e, _| parse(&e.points_per_game), format: |e, _| e.points_per_game.clone() },
+    Stat { header: "ICT", width: 6, value: |e, _| parse(&e.ict_index), format: |e, _| e.ict_index.clone() },
+    Stat {
+        header: "xGI",
+        width: 5,
+        value: |e, _| parse(&e.expected_goal_involvements),
+        format: |e, _| e.expected_goal_involvements.clone(),
+    },
+    Stat { header: "Mins", width: 5, value: |e, _| e.minutes as f64, format: |e, _| e.minutes.to_string() },
+    Stat { header: "G", width: 3, value: |e, _| e.goals_scored as f64, format: |e, _| e.goals_scored.to_string() },
+    Stat { header: "A", width: 3, value: |e, _| e.assists as f64, format: |e, _| e.assists.to_string() },
+    Stat { header: "Bonus", width: 5, value: |e, _| e.bonus as f64, format: |e, _| e.bonus.to_string() },
+    Stat {
+        header: "Sel%",
+        width: 5,
+        value: |e, _| parse(&e.selected_by_percent),
+        format: |e, _| e.selected_by_percent.clone(),
+    },
+];
+
+/// The column the table is first sorted by.
+const DEFAULT_SORT: &str = "Pts";
+
+const POSITIONS: [&str; 5] = ["", "GK", "DEF", "MID", "FWD"];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Availability {
+    #[default]
+    Any,
+    Available,
+    Flagged,
+}
+
+impl Availability {
+    fn next(self) -> Self {
+        match self {
+            Availability::Any => Availability::Available,
+            Availability::Available => Availability::Flagged,
+            Availability::Flagged => Availability::Any,
+        }
+    }
+
+    fn matches(self, element: &Element) -> bool {
+        match self {
+            Availability::Any => true,
+            Availability::Available => element.status == "a",
+            Availability::Flagged => element.status != "a",
+        }
+    }
+}
+
+/// Every player in the game, with filters, sorting and search.
+pub struct PlayerBrowser {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    elements: Vec<Element>,
+    teams: Vec<Team>,
+    picker: Option<Picker>,
+    team_to_badge: HashMap<i64, DynamicImage>,
+    projection: Projection,
+    prices: Arc<PriceHistory>,
+
+    // Filters, sorting and search
+    /// `element_type` to show, or 0 for every position.
+    position: i64,
+    /// Index into `teams`, if filtering by team.
+    team: Option<usize>,
+    /// Price range in tenths of a million.
+    min_cost: i64,
+    max_cost: i64,
+    availability: Availability,
+    sort: usize,
+    descending: bool,
+    query: String,
+    searching: bool,
+    /// Picking a replacement for the planner, so Enter hands the player back instead of opening a card.
+    choosing: bool,
+
+    /// Element ids in the order they're shown.
+    rows: Vec<i64>,
+    table_state: TableState,
+    card: Option<PlayerCard>,
+    /// Element id of the player whose price chart is open.
+    chart: Option<i64>,
+}
+
+const PRICE_STEP: i64 = 5;
+
+impl PlayerBrowser {
+    pub fn new(snapshot: &Snapshot, picker: Option<Picker>, team_to_badge: HashMap<i64, DynamicImage>) -> Self {
+        let mut browser = Self {
+            command_tx: None,
+            config: Config::default(),
+            mode: Mode::Home,
+            elements: Vec::new(),
+            teams: Vec::new(),
+            picker,
+            team_to_badge,
+            projection: Projection::default(),
+            prices: Arc::default(),
+            position: 0,
+            team: None,
+            min_cost: 0,
+            max_cost: 0,
+            availability: Availability::default(),
+            sort: STATS.iter().position(|s| s.header == DEFAULT_SORT).unwrap_or(0),
+            descending: true,
+            query: String::new(),
+            searching: false,
+            choosing: false,
+            rows: Vec::new(),
+            table_state: TableState::default().with_selected(Some(0)),
+            card: None,
+            chart: None,
+        };
+        browser.load(snapshot);
+        browser.min_cost = browser.cheapest();
+        browser.max_cost = browser.priciest();
+        browser.apply();
+        browser
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.elements = snapshot.bootstrap.elements.clone();
+        self.teams = snapshot.bootstrap.teams.clone();
+        self.teams.sort_by(|a, b| a.name.cmp(&b.name));
+        self.projection =
+            Projection::new(&snapshot.bootstrap, &snapshot.fixtures, self.config.config.projection_gameweeks);
+    }
+
+    fn cheapest(&self) -> i64 {
+        self.elements.iter().map(|e| e.now_cost).min().unwrap_or(0)
+    }
+
+    fn priciest(&self) -> i64 {
+        self.elements.iter().map(|e| e.now_cost).max().unwrap_or(0)
+    }
+
+    fn element(&self, id: i64) -> Option<&Element> {
+        self.elements.iter().find(|e| e.id == id)
+    }
+
+    fn team_name(&self, team: i64) -> &str {
+        self.teams.iter().find(|t| t.id == team).map_or("", |t| t.short_name.as_str())
+    }
+
+    /// Rebuild the rows from the filters, sort and search, keeping the selected player if still shown.
+    fn apply(&mut self) {
+        let selected = self.table_state.selected().and_then(|i| self.rows.get(i)).copied();
+        let team = self.team.and_then(|i| self.teams.get(i)).map(|t| t.id);
+        let mut rows: Vec<(Option<i64>, f64, i64)> = self
+            .elements
+            .iter()
+            .filter(|e| self.position == 0 || e.element_type == self.position)
+            .filter(|e| team.is_none_or(|t| e.team == t))
+            .filter(|e| (self.min_cost..=self.max_cost).contains(&e.now_cost))
+            .filter(|e| self.availability.matches(e))
+            .filter_map(|e| {
+                let score = match self.query.is_empty() {
+                    true => None,
+                    false => Some(fuzzy::score(&self.query, &format!("{} {}", e.first_name, e.second_name))?),
+                };
+                Some((score, (STATS[self.sort].value)(e, self), e.id))
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let by_stat = match self.descending {
+                true => b.1.total_cmp(&a.1),
+                false => a.1.total_cmp(&b.1),
+            };
+            b.0.cmp(&a.0).then(by_stat)
+        });
+        self.rows = rows.into_iter().map(|(_, _, id)| id).collect();
+        let index = selected.and_then(|id| self.rows.iter().position(|&r| r == id)).unwrap_or(0);
+        self.table_state.select(Some(index));
+    }
+
+    fn open_card(&mut self) -> Option<Action> {
+        let element =
+            self.table_state.selected().and_then(|i| self.rows.get(i)).and_then(|&id| self.element(id))?.clone();
+        let team_name = self.teams.iter().find(|t| t.id == element.team).map_or(String::new(), |t| t.name.clone());
+        let badge = match self.picker.as_mut() {
+            Some(picker) => self.team_to_badge.get(&element.team_code).map(|b| picker.new_resize_protocol(b.clone())),
+            None => None,
+        };
+        let pick = Pick { element: element.id, multiplier: 1, ..Default::default() };
+        let code = element.code;
+        let mut card = PlayerCard::new(
+            format!("{} {}", element.first_name, element.second_name),
+            team_name,
+            element,
+            self.picker,
+            badge,
+            pick,
+        );
+        card.set_projection(self.projection.upcoming(card.details.id));
+        card.set_price_change(self.prices.since_last_run(card.details.id, card.details.now_cost));
+        self.card = Some(card);
+        Some(Action::GetPlayerImage(code))
+    }
+
+    /// Preset the filters for a replacement in `position` costing up to `budget`.
+    fn choose(&mut self, position: i64, budget: i64) {
+        self.choosing = true;
+        self.card = None;
+        self.position = position;
+        self.team = None;
+        self.query.clear();
+        self.min_cost = self.cheapest();
+        self.max_cost = budget.clamp(self.cheapest(), self.priciest());
+        self.table_state.select(Some(0));
+        self.apply();
+    }
+
+    fn chosen(&mut self) -> Option<Action> {
+        self.choosing = false;
+        let element = self.table_state.selected().and_then(|i| self.rows.get(i)).copied()?;
+        if let Some(tx) = &self.command_tx {
+            let _ = tx.send(Action::PlayerChosen(element));
+        }
+        Some(Action::SwitchMode(Mode::Planner))
+    }
+
+    fn handle_action(&mut self, action: Action) -> Option<Action> {
+        if self.card.is_some() || self.chart.is_some() {
+            if action == Action::Escape {
+                self.card = None;
+                self.chart = None;
+            }
+            return None;
+        }
+        match action {
+            Action::Escape if self.choosing => {
+                self.choosing = false;
+                return Some(Action::SwitchMode(Mode::Planner));
+            },
+            Action::Escape => return Some(Action::SwitchMode(Mode::Home)),
+            Action::Enter if self.choosing => return self.chosen(),
+            Action::Enter => return self.open_card(),
+            Action::Search => return self.set_searching(true),
+            Action::Chart => self.chart = self.table_state.selected().and_then(|i| self.rows.get(i)).copied(),
+            Action::Up => self.table_state.select_previous(),
+            Action::Down => self.table_state.select_next(),
+            Action::PageUp => self.table_state.scroll_up_by(20),
+            Action::PageDown => self.table_state.scroll_down_by(20),
+            Action::CyclePosition => self.position = (self.position + 1) % POSITIONS.len() as i64,
+            Action::CycleTeam => {
+                self.team = match self.team {
+                    None if !self.teams.is_empty() => Some(0),
+                    Some(i) if i + 1 < self.teams.len() => Some(i + 1),
+                    _ => None,
+                }
+            },
+            Action::LowerMinPrice => self.min_cost = (self.min_cost - PRICE_STEP).max(self.cheapest()),
+            Action::RaiseMinPrice => self.min_cost = (self.min_cost + PRICE_STEP).min(self.max_cost),
+            Action::LowerMaxPrice => self.max_cost = (self.max_cost - PRICE_STEP).max(self.min_cost),
+            Action::RaiseMaxPrice => self.max_cost = (self.max_cost + PRICE_STEP).min(self.priciest()),
+            Action::CycleAvailability => self.availability = self.availability.next(),
+            Action::CycleSort => self.sort = (self.sort + 1) % STATS.len(),
+            Action::ReverseSort => self.descending = !self.descending,
+            _ => return None,
+        }
+        self.apply();
+        None
+    }
+
+    fn set_searching(&mut self, searching: bool) -> Option<Action> {
+        self.searching = searching;
+        Some(Action::CaptureKeys(searching))
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<Action> {
+        // Ctrl-c and the like are left to the app rather than typed
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return None;
+        }
+        match key.code {
+            KeyCode::Esc => {
+                self.query.clear();
+                self.apply();
+                self.set_searching(false)
+            },
+            KeyCode::Enter => self.set_searching(false),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.apply();
+                None
+            },
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.apply();
+                None
+            },
+            KeyCode::Up => {
+                self.table_state.select_previous();
+                None
+            },
+            KeyCode::Down => {
+                self.table_state.select_next();
+                None
+            },
+            _ => None,
+        }
+    }
+
+    fn filter_line(&self) -> Line<'_> {
+        let team = self.team.and_then(|i| self.teams.get(i)).map_or("All", |t| t.name.as_str());
+        let position = match self.position {
+            0 => "All",
+            p => POSITIONS[p as usize],
+        };
+        let availability = match self.availability {
+            Availability::Any => "Any",
+            Availability::Available => "Available",
+            Availability::Flagged => "Flagged",
+        };
+        let direction = if self.descending { "↓" } else { "↑" };
+        let field = |actions: &[Action], label: &'static str, value: String| {
+            let keys: Vec<String> =
+                actions.iter().filter_map(|a| self.config.keybindings.keys(Mode::Browser, a)).collect();
+            vec![
+                Span::raw(keys.join("/")).bold(),
+                Span::raw(format!(" {}: ", label)).dim(),
+                Span::raw(format!("{}   ", value)),
+            ]
+        };
+        let mut spans = Vec::new();
+        spans.extend(field(&[Action::CyclePosition], "Position", position.to_string()));
+        spans.extend(field(&[Action::CycleTeam], "Team", team.to_string()));
+        let (min, max) = (self.min_cost as f64 / 10.0, self.max_cost as f64 / 10.0);
+        spans.extend(field(&[Action::LowerMinPrice, Action::RaiseMinPrice], "Min", format!("£{:.1}m", min)));
+        spans.extend(field(&[Action::LowerMaxPrice, Action::RaiseMaxPrice], "Max", format!("£{:.1}m", max)));
+        spans.extend(field(&[Action::CycleAvailability], "Availability", availability.to_string()));
+        let sort = format!("{} {}", STATS[self.sort].header, direction);
+        spans.extend(field(&[Action::CycleSort, Action::ReverseSort], "Sort", sort));
+        Line::from(spans)
+    }
+
+    fn draw_table(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let header = Row::new(["Name", "Team", "Pos", ""].into_iter().map(Cell::from).chain(
+            STATS.iter().enumerate().map(|(i, s)| {
+                let cell = Cell::from(Line::from(s.header).right_aligned());
+                if i == self.sort {
+                    cell.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                } else {
+                    cell
+                }
+            }),
+        ))
+        .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = self.rows.iter().filter_map(|&id| self.element(id)).map(|e| {
+            let status = match e.status.as_str() {
+                "a" => Span::raw(""),
+                "d" => Span::styled("⚠", Style::default().fg(Color::Yellow)),
+                _ => Span::styled("✖", Style::default().fg(Color::Red)),
+            };
+            Row::new(
+                [
+                    Cell::from(e.web_name.clone()),
+                    Cell::from(self.team_name(e.team).to_string()),
+                    Cell::from(POSITIONS.get(e.element_type as usize).copied().unwrap_or("")),
+                    Cell::from(status),
+                ]
+                .into_iter()
+                .chain(STATS.iter().map(|s| Cell::from(Line::from((s.format)(e, self)).right_aligned()))),
+            )
+        });
+        let widths = [Constraint::Min(16), Constraint::Length(4), Constraint::Length(4), Constraint::Length(2)]
+            .into_iter()
+            .chain(STATS.iter().map(|s| Constraint::Length(s.width)));
+        let (title, help) = match self.choosing {
+            true => {
+                (
+                    "Choose a replacement",
+                    self.config.keybindings.hints(Mode::Browser, &[
+                        (&[Action::Search], "search"),
+                        (&[Action::Enter], "choose"),
+                        (&[Action::Escape], "cancel"),
+                    ]),
+                )
+            },
+            false => {
+                (
+                    "Players",
+                    self.config.keybindings.hints(Mode::Browser, &[
+                        (&[Action::Search], "search"),
+                        (&[Action::Enter], "open card"),
+                        (&[Action::Chart], "price chart"),
+                        (&[Action::Escape], "back"),
+                    ]),
+                )
+            },
+        };
+        let title = format!(" {} ({}) · xP over {} GWs ", title, self.rows.len(), self.projection.horizon());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_bottom(Line::from(help).dim().right_aligned()),
+            );
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+impl Component for PlayerBrowser {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.projection.set_horizon(config.config.projection_gameweeks);
+        self.config = config;
+        self.apply();
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Key(key_event)) if self.mode == Mode::Browser && self.searching => {
+                self.handle_search_key(key_event)
+            },
+            Some(Event::PlayerImage(code, image)) => {
+                if let Some(card) = self.card.as_mut().filter(|c| c.details.code == code) {
+                    card.set_image(image);
+                }
+                None
+            },
+            Some(Event::Refreshed(snapshot)) => {
+                self.load(&snapshot);
+                self.apply();
+                None
+            },
+            Some(Event::PriceHistory(prices)) => {
+                self.prices = prices;
+                if let Some(card) = self.card.as_mut() {
+                    card.set_price_change(self.prices.since_last_run(card.details.id, card.details.now_cost));
+                }
+                self.apply();
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SwitchMode(mode) => self.mode = mode,
+            Action::ChoosePlayer(position, budget) => self.choose(position, budget),
+            action if self.mode == Mode::Browser => return Ok(self.handle_action(action)),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Browser {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Min(5)])
+            .split(area);
+        let search = match (self.searching, self.query.is_empty()) {
+            (true, _) => Line::from(vec![Span::raw(self.query.clone()), Span::raw("▏").slow_blink()]),
+            (false, true) => {
+                match self.config.keybindings.keys(Mode::Browser, &Action::Search) {
+                    Some(keys) => Line::from(format!("press {} to search by name", keys)).dim(),
+                    None => Line::from("search by name").dim(),
+                }
+            },
+            (false, false) => Line::from(self.query.clone()),
+        };
+        f.render_widget(
+            Paragraph::new(search).block(Block::default().borders(Borders::ALL).title(" Search ")),
+            layout[0],
+        );
+        f.render_widget(
+            Paragraph::new(self.filter_line()).block(Block::default().borders(Borders::ALL).title(" Filters ")),
+            layout[1],
+        );
+        self.draw_table(f, layout[2]);
+        if let Some(card) = self.card.as_mut() {
+            let card_area = Layout::default().constraints([Constraint::Percentage(100)]).margin(4).split(area)[0];
+            card.draw_big(f, card_area)?;
+        }
+        if let Some(element) = self.chart.and_then(|id| self.element(id)) {
+            let chart_area = Layout::default().constraints([Constraint::Percentage(100)]).margin(4).split(area)[0];
+            let hint = self.config.keybindings.hints(Mode::Browser, &[(&[Action::Escape], "close")]);
+            price_chart::draw(f, chart_area, &element.web_name, &self.prices.prices(element.id), hint);
+        }
+        Ok(())
+    }
+}
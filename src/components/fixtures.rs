@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::Result;
+use fpl_api::{
+    bootstrap::{BootstrapData, Team},
+    fixture::Fixture,
+};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::{
+    action::Action, config::Config, data_source::Snapshot, event::Event, fdr, mode::Mode, utils::format_kickoff,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum View {
+    #[default]
+    List,
+    Grid,
+}
+
+/// Fixtures by gameweek, and each team's upcoming fixture difficulty.
+pub struct Fixtures {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    view: View,
+    fixtures: Vec<Fixture>,
+    teams: HashMap<i64, Team>,
+    /// The first gameweek that hasn't finished, where the difficulty grid starts.
+    next_event: i64,
+    last_event: i64,
+    /// The gameweek shown in the list.
+    gameweek: i64,
+    grid_state: TableState,
+}
+
+fn first_unfinished_event(bootstrap: &BootstrapData) -> i64 {
+    bootstrap.events.iter().find(|e| !e.finished).or(bootstrap.events.last()).map_or(1, |e| e.id)
+}
+
+/// How a fixture is getting on: kickoff time, live minutes or full time.
+fn state(fixture: &Fixture) -> Span<'static> {
+    if fixture.finished || fixture.finished_provisional {
+        Span::raw("FT").dim()
+    } else if fixture.started {
+        Span::styled(format!("LIVE {}'", fixture.minutes), Style::default().fg(Color::Green).bold())
+    } else {
+        Span::raw(format_kickoff(&fixture.kickoff_time))
+    }
+}
+
+impl Fixtures {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let mut fixtures = Self {
+            command_tx: None,
+            config: Config::default(),
+            mode: Mode::Home,
+            view: View::default(),
+            fixtures: Vec::new(),
+            teams: HashMap::new(),
+            next_event: 1,
+            last_event: 1,
+            gameweek: 1,
+            grid_state: TableState::default(),
+        };
+        fixtures.load(snapshot);
+        fixtures.gameweek = fixtures.next_event;
+        fixtures
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.fixtures = snapshot.fixtures.clone();
+        self.teams = snapshot.bootstrap.teams.iter().map(|t| (t.id, t.clone())).collect();
+        self.next_event = first_unfinished_event(&snapshot.bootstrap);
+        self.last_event = snapshot.bootstrap.events.iter().map(|e| e.id).max().unwrap_or(1);
+    }
+
+    fn short_name(&self, team: i64) -> String {
+        self.teams.get(&team).map_or_else(|| team.to_string(), |t| t.short_name.clone())
+    }
+
+    fn draw_list(&self, f: &mut Frame<'_>, area: Rect) {
+        let mut fixtures: Vec<&Fixture> = self.fixtures.iter().filter(|f| f.event == self.gameweek).collect();
+        fixtures.sort_by(|a, b| a.kickoff_time.cmp(&b.kickoff_time));
+        let rows = fixtures.iter().map(|f| {
+            let score = match (f.team_h_score, f.team_a_score) {
+                (Some(h), Some(a)) if f.started => format!("{} - {}", h, a),
+                _ => "v".to_string(),
+            };
+            Row::new(vec![
+                Cell::from(Line::from(self.teams.get(&f.team_h).map_or("", |t| t.name.as_str())).right_aligned()),
+                Cell::from(Line::from(score).centered().bold()),
+                Cell::from(self.teams.get(&f.team_a).map_or("", |t| t.name.as_str())),
+                Cell::from(Line::from(state(f)).right_aligned()),
+            ])
+        });
+        let keys = |action| self.config.keybindings.keys(Mode::Fixtures, &action).unwrap_or_default();
+        let table =
+            Table::new(rows, [Constraint::Min(16), Constraint::Length(7), Constraint::Min(16), Constraint::Length(12)])
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(Line::from(vec![
+                            Span::raw(format!(" ◀ {} ", keys(Action::PreviousGameweek))).dim(),
+                            Span::raw(format!(" GW{} fixtures ", self.gameweek)).bold(),
+                            Span::raw(format!("{} ▶ ", keys(Action::NextGameweek))).dim(),
+                        ]))
+                        .title_bottom(
+                            Line::from(self.config.keybindings.hints(Mode::Fixtures, &[
+                                (&[Action::NextPane], "difficulty grid"),
+                                (&[Action::Escape], "back"),
+                            ]))
+                            .dim()
+                            .right_aligned(),
+                        ),
+                );
+        f.render_widget(table, area);
+    }
+
+    fn draw_grid(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let first = self.next_event;
+        let count = self.config.config.fdr_gameweeks.clamp(1, (self.last_event - first + 1).max(1));
+        let schedule = fdr::schedule(&self.fixtures, first, count);
+        let mut teams: Vec<&Team> = self.teams.values().collect();
+        teams.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let header =
+            Row::new(std::iter::once("Team".to_string()).chain((first..first + count).map(|gw| format!("GW{}", gw))))
+                .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = teams.iter().map(|team| {
+            let gameweeks = schedule.get(&team.id);
+            let cells = (0..count as usize).map(|i| {
+                let fixtures = gameweeks.map(|g| g[i].as_slice()).unwrap_or_default();
+                let Some(hardest) = fixtures.iter().map(|f| f.difficulty).max() else {
+                    return Cell::from("—").style(Style::default().dim());
+                };
+                // Home fixtures in capitals, away in lower case, as on the FPL site
+                let text = fixtures
+                    .iter()
+                    .map(|f| {
+                        match f.is_home {
+                            true => self.short_name(f.opponent),
+                            false => self.short_name(f.opponent).to_lowercase(),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let (bg, fg) = fdr::difficulty_colours(hardest);
+                let style = Style::default().bg(bg).fg(fg);
+                Cell::from(text).style(if fixtures.len() > 1 { style.bold() } else { style })
+            });
+            Row::new(std::iter::once(Cell::from(team.name.clone())).chain(cells))
+        });
+        let widths = std::iter::once(Constraint::Length(16)).chain((0..count).map(|_| Constraint::Length(8)));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(1)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(
+                Block::default().borders(Borders::ALL).title(" Fixture difficulty ").title_bottom(
+                    Line::from(format!(
+                        " HOME · away · bold double · — blank ·{}",
+                        self.config.keybindings.hints(Mode::Fixtures, &[
+                            (&[Action::NextPane], "fixture list"),
+                            (&[Action::Escape], "back"),
+                        ])
+                    ))
+                    .dim()
+                    .right_aligned(),
+                ),
+            );
+        f.render_stateful_widget(table, area, &mut self.grid_state);
+    }
+}
+
+impl Component for Fixtures {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Refreshed(snapshot)) => {
+                self.load(&snapshot);
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::SwitchMode(mode) = action {
+            self.mode = mode;
+            return Ok(None);
+        }
+        if self.mode != Mode::Fixtures {
+            return Ok(None);
+        }
+        match (action, self.view) {
+            (Action::Escape, _) => return Ok(Some(Action::SwitchMode(Mode::Home))),
+            (Action::NextPane, View::List) => self.view = View::Grid,
+            (Action::NextPane, View::Grid) => self.view = View::List,
+            (Action::PreviousGameweek, View::List) => self.gameweek = (self.gameweek - 1).max(1),
+            (Action::NextGameweek, View::List) => self.gameweek = (self.gameweek + 1).min(self.last_event),
+            (Action::Up, View::Grid) => self.grid_state.select_previous(),
+            (Action::Down, View::Grid) => self.grid_state.select_next(),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Fixtures {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        match self.view {
+            View::List => self.draw_list(f, area),
+            View::Grid => self.draw_grid(f, area),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, HashSet};
+
+use fpl_api::{
+    bootstrap::Element,
+    fixture::Fixtures,
+    manager::{GWTeam, Pick},
+    players::{Element as LiveElement, GWLiveData},
+};
+use ratatui::{prelude::*, widgets::*};
+use thousands::Separable;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Frame;
+use crate::{
+    action::Action,
+    api::{H2HMatch, H2HMatches, H2HStandings},
+    config::KeyBindings,
+    data_source::Snapshot,
+    lineup,
+    mode::Mode,
+    points,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Standings,
+    Matches,
+}
+
+/// The H2H league being looked at.
+struct OpenLeague {
+    league_id: i64,
+    page: i64,
+    gameweek: i64,
+    pane: Pane,
+    /// Gameweek and entries of the matchup whose squads are open.
+    matchup: Option<(i64, [i64; 2])>,
+}
+
+/// Both squads of a matchup along with the gameweek's points.
+struct Matchup {
+    teams: [GWTeam; 2],
+    live: HashMap<i64, LiveElement>,
+}
+
+/// Standings, matches and matchups for the manager's H2H leagues.
+///
+/// Lives inside [`super::leagues::Leagues`], which hands over keys and drawing while a league is open.
+#[derive(Default)]
+pub struct H2H {
+    command_tx: Option<UnboundedSender<Action>>,
+    keybindings: KeyBindings,
+    elements: HashMap<i64, Element>,
+    /// For telling who missed a gameweek, to make automatic substitutions in matchups.
+    fixtures: Fixtures,
+    current_event: i64,
+    open: Option<OpenLeague>,
+    standings: HashMap<(i64, i64), H2HStandings>,
+    /// Matches keyed by league and gameweek.
+    matches: HashMap<(i64, i64), H2HMatches>,
+    matchups: HashMap<(i64, [i64; 2]), Matchup>,
+    requested: HashSet<Action>,
+    standings_state: TableState,
+    matches_state: TableState,
+}
+
+fn result_style(points: i64, other: i64) -> Style {
+    match points.cmp(&other) {
+        std::cmp::Ordering::Greater => Style::default().fg(Color::Green),
+        std::cmp::Ordering::Less => Style::default().fg(Color::Red),
+        std::cmp::Ordering::Equal => Style::default(),
+    }
+}
+
+fn position_name(element_type: i64) -> &'static str {
+    match element_type {
+        1 => "GK",
+        2 => "DEF",
+        3 => "MID",
+        4 => "FWD",
+        lineup::ASSISTANT_MANAGER => "AM",
+        _ => "",
+    }
+}
+
+impl H2H {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let mut h2h = Self::default();
+        h2h.load(snapshot);
+        h2h
+    }
+
+    pub fn register_action_handler(&mut self, tx: UnboundedSender<Action>) {
+        self.command_tx = Some(tx);
+    }
+
+    pub fn register_keybindings(&mut self, keybindings: KeyBindings) {
+        self.keybindings = keybindings;
+    }
+
+    /// Hints for the keys bound to `entries` in the leagues screen, see [`KeyBindings::hints`].
+    fn hints(&self, entries: &[(&[Action], &str)]) -> String {
+        self.keybindings.hints(Mode::Leagues, entries)
+    }
+
+    pub fn load(&mut self, snapshot: &Snapshot) {
+        self.elements = snapshot.bootstrap.elements.iter().map(|e| (e.id, e.clone())).collect();
+        self.fixtures = snapshot.fixtures.clone();
+        self.current_event = snapshot.manager.current_event;
+    }
+
+    /// The picks as they score in `gameweek`, after automatic substitutions and any change of armband.
+    fn effective_picks(&self, picks: &[Pick], gameweek: i64, live: &HashMap<i64, LiveElement>) -> Vec<Pick> {
+        let mut picks = points::live_effective_picks(picks, gameweek, &self.elements, &self.fixtures, live);
+        picks.sort_by_key(|p| p.position);
+        picks
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    pub fn open(&mut self, league_id: i64) {
+        self.open =
+            Some(OpenLeague { league_id, page: 1, gameweek: self.current_event, pane: Pane::Standings, matchup: None });
+        self.standings_state.select(Some(0));
+        self.matches_state.select(Some(0));
+        self.request_missing();
+    }
+
+    /// Forget everything loaded so far, asking again for whatever is on screen.
+    pub fn clear(&mut self) {
+        self.standings.clear();
+        self.matches.clear();
+        self.matchups.clear();
+        self.requested.clear();
+        self.request_missing();
+    }
+
+    /// Forget a load that failed, so that it's asked for again.
+    pub fn request_failed(&mut self, request: &Action) {
+        self.requested.remove(request);
+    }
+
+    pub fn set_standings(&mut self, page: i64, standings: H2HStandings) {
+        let key = (standings.league.id, page);
+        self.requested.remove(&Action::LoadH2HStandings(key.0, key.1));
+        self.standings.insert(key, standings);
+    }
+
+    pub fn set_matches(&mut self, league_id: i64, gameweek: i64, matches: H2HMatches) {
+        self.requested.remove(&Action::LoadH2HMatches(league_id, gameweek));
+        self.matches.insert((league_id, gameweek), matches);
+    }
+
+    pub fn set_matchup(&mut self, gameweek: i64, entries: [i64; 2], teams: [GWTeam; 2], live: Option<GWLiveData>) {
+        self.requested.remove(&Action::LoadMatchup(gameweek, entries[0], entries[1]));
+        let live = live.as_ref().map(points::index_live).unwrap_or_default();
+        self.matchups.insert((gameweek, entries), Matchup { teams, live });
+    }
+
+    fn request(&mut self, action: Action) {
+        if let Some(tx) = &self.command_tx {
+            if self.requested.insert(action.clone()) {
+                let _ = tx.send(action);
+            }
+        }
+    }
+
+    /// Ask for whatever the open league needs on screen that we don't have yet.
+    fn request_missing(&mut self) {
+        let Some(open) = &self.open else {
+            return;
+        };
+        let mut wanted = Vec::new();
+        if !self.standings.contains_key(&(open.league_id, open.page)) {
+            wanted.push(Action::LoadH2HStandings(open.league_id, open.page));
+        }
+        if !self.matches.contains_key(&(open.league_id, open.gameweek)) {
+            wanted.push(Action::LoadH2HMatches(open.league_id, open.gameweek));
+        }
+        if let Some((gameweek, entries)) = open.matchup {
+            if !self.matchups.contains_key(&(gameweek, entries)) {
+                wanted.push(Action::LoadMatchup(gameweek, entries[0], entries[1]));
+            }
+        }
+        for action in wanted {
+            self.request(action);
+        }
+    }
+
+    fn selected_match(&self) -> Option<&H2HMatch> {
+        let open = self.open.as_ref()?;
+        let matches = self.matches.get(&(open.league_id, open.gameweek))?;
+        matches.results.get(self.matches_state.selected()?)
+    }
+
+    /// Esc closes an open matchup, and otherwise the league itself.
+    pub fn handle_action(&mut self, action: Action) {
+        let Some(open) = self.open.as_mut() else {
+            return;
+        };
+        if open.matchup.is_some() {
+            if action == Action::Escape {
+                open.matchup = None;
+            }
+            return;
+        }
+        match (action, open.pane) {
+            (Action::Escape, _) => self.open = None,
+            (Action::NextPane, Pane::Standings) => open.pane = Pane::Matches,
+            (Action::NextPane, Pane::Matches) => open.pane = Pane::Standings,
+            (Action::Up, Pane::Standings) => self.standings_state.select_previous(),
+            (Action::Down, Pane::Standings) => self.standings_state.select_next(),
+            (Action::Up, Pane::Matches) => self.matches_state.select_previous(),
+            (Action::Down, Pane::Matches) => self.matches_state.select_next(),
+            (Action::Left, Pane::Standings) if open.page > 1 => {
+                open.page -= 1;
+                self.standings_state.select(Some(0));
+            },
+            (Action::Right, Pane::Standings) => {
+                if self.standings.get(&(open.league_id, open.page)).is_some_and(|s| s.standings.has_next) {
+                    open.page += 1;
+                    self.standings_state.select(Some(0));
+                }
+            },
+            (Action::PreviousGameweek, Pane::Matches) if open.gameweek > 1 => {
+                open.gameweek -= 1;
+                self.matches_state.select(Some(0));
+            },
+            (Action::NextGameweek, Pane::Matches) if open.gameweek < self.current_event => {
+                open.gameweek += 1;
+                self.matches_state.select(Some(0));
+            },
+            (Action::Enter, Pane::Matches) => {
+                let gameweek = open.gameweek;
+                let entries = self.selected_match().and_then(|m| Some([m.entry_1_entry?, m.entry_2_entry?]));
+                if let (Some(entries), Some(open)) = (entries, self.open.as_mut()) {
+                    open.matchup = Some((gameweek, entries));
+                }
+            },
+            _ => {},
+        }
+        self.request_missing();
+    }
+
+    pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let Some(open) = &self.open else {
+            return;
+        };
+        if let Some((gameweek, entries)) = open.matchup {
+            self.draw_matchup(f, area, gameweek, entries);
+            return;
+        }
+        let (league_id, page, gameweek, pane) = (open.league_id, open.page, open.gameweek, open.pane);
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(area);
+        self.draw_standings(f, layout[0], league_id, page, pane == Pane::Standings);
+        self.draw_matches(f, layout[1], league_id, gameweek, pane == Pane::Matches);
+    }
+
+    fn pane_block(title: String, focused: bool, hint: String) -> Block<'static> {
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if focused {
+            block
+                .border_style(Style::default().fg(Color::Indexed(127_u8)))
+                .title_bottom(Line::from(hint).dim().right_aligned())
+        } else {
+            block
+        }
+    }
+
+    fn draw_standings(&mut self, f: &mut Frame<'_>, area: Rect, league_id: i64, page: i64, focused: bool) {
+        let Some(standings) = self.standings.get(&(league_id, page)) else {
+            let p = Paragraph::new("Loading standings…").alignment(Alignment::Center).block(Self::pane_block(
+                " Standings ".to_string(),
+                focused,
+                String::new(),
+            ));
+            f.render_widget(p, area);
+            return;
+        };
+        let header = Row::new(["Rank", "Team", "Manager", "W", "D", "L", "Score", "Pts"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = standings.standings.results.iter().map(|e| {
+            Row::new(vec![
+                e.rank.to_string(),
+                e.entry_name.clone(),
+                e.player_name.clone(),
+                e.matches_won.to_string(),
+                e.matches_drawn.to_string(),
+                e.matches_lost.to_string(),
+                e.points_for.separate_with_commas(),
+                e.total.to_string(),
+            ])
+        });
+        let table = Table::new(rows, [
+            Constraint::Length(5),
+            Constraint::Min(16),
+            Constraint::Min(16),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(6),
+            Constraint::Length(4),
+        ])
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Self::pane_block(
+            format!(" {} · page {} ", standings.league.name, page),
+            focused,
+            self.hints(&[
+                (&[Action::Left, Action::Right], "page"),
+                (&[Action::NextPane], "matches"),
+                (&[Action::Escape], "back"),
+            ]),
+        ));
+        f.render_stateful_widget(table, area, &mut self.standings_state);
+    }
+
+    fn draw_matches(&mut self, f: &mut Frame<'_>, area: Rect, league_id: i64, gameweek: i64, focused: bool) {
+        let title = match gameweek == self.current_event {
+            true => format!(" GW{} matchups ", gameweek),
+            false => format!(" GW{} results ", gameweek),
+        };
+        let Some(matches) = self.matches.get(&(league_id, gameweek)) else {
+            let p = Paragraph::new("Loading matches…").alignment(Alignment::Center).block(Self::pane_block(
+                title,
+                focused,
+                String::new(),
+            ));
+            f.render_widget(p, area);
+            return;
+        };
+        let rows = matches.results.iter().map(|m| {
+            Row::new(vec![
+                Cell::from(m.entry_1_name.clone()),
+                Cell::from(
+                    Line::from(vec![
+                        Span::styled(m.entry_1_points.to_string(), result_style(m.entry_1_points, m.entry_2_points)),
+                        Span::raw(" - "),
+                        Span::styled(m.entry_2_points.to_string(), result_style(m.entry_2_points, m.entry_1_points)),
+                    ])
+                    .centered(),
+                ),
+                Cell::from(Line::from(m.entry_2_name.clone()).right_aligned()),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Min(12), Constraint::Length(11), Constraint::Min(12)])
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Self::pane_block(
+                title,
+                focused,
+                self.hints(&[
+                    (&[Action::PreviousGameweek, Action::NextGameweek], "gameweek"),
+                    (&[Action::Enter], "squads"),
+                    (&[Action::NextPane], "standings"),
+                ]),
+            ));
+        f.render_stateful_widget(table, area, &mut self.matches_state);
+    }
+
+    fn draw_matchup(&self, f: &mut Frame<'_>, area: Rect, gameweek: i64, entries: [i64; 2]) {
+        f.render_widget(Clear, area);
+        let Some(matchup) = self.matchups.get(&(gameweek, entries)) else {
+            let p = Paragraph::new("Loading squads…")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title(format!(" GW{} ", gameweek)));
+            f.render_widget(p, area);
+            return;
+        };
+        let names = self
+            .open
+            .as_ref()
+            .and_then(|o| self.matches.get(&(o.league_id, gameweek)))
+            .and_then(|m| {
+                m.results.iter().find(|m| m.entry_1_entry == Some(entries[0]) && m.entry_2_entry == Some(entries[1]))
+            })
+            .map(|m| [m.entry_1_name.clone(), m.entry_2_name.clone()])
+            .unwrap_or_else(|| entries.map(|e| e.to_string()));
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        for (i, team) in matchup.teams.iter().enumerate() {
+            self.draw_squad(f, layout[i], &names[i], gameweek, team, &matchup.live);
+        }
+    }
+
+    fn draw_squad(
+        &self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        name: &str,
+        gameweek: i64,
+        team: &GWTeam,
+        live: &HashMap<i64, LiveElement>,
+    ) {
+        let points_for = |element: i64| live.get(&element).map_or(0, |e| e.stats.total_points);
+        let picks = self.effective_picks(&team.picks, gameweek, live);
+        let rows = picks.iter().map(|p| {
+            let details = self.elements.get(&p.element);
+            let mut player = details.map_or_else(|| p.element.to_string(), |e| e.web_name.clone());
+            if p.is_captain {
+                player.push_str(" (C)");
+            } else if p.is_vice_captain {
+                player.push_str(" (V)");
+            }
+            let row = Row::new(vec![
+                position_name(details.map_or(0, |e| e.element_type)).to_string(),
+                player,
+                (p.multiplier.max(1) * points_for(p.element)).to_string(),
+            ]);
+            if p.multiplier == 0 {
+                row.dim()
+            } else {
+                row
+            }
+        });
+        let total = points::gw_total(&picks, points_for) - team.entry_history.event_transfers_cost;
+        let table = Table::new(rows, [Constraint::Length(4), Constraint::Min(16), Constraint::Length(4)]).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} · {} pts ", name, total))
+                .title_bottom(Line::from(self.hints(&[(&[Action::Escape], "back")])).dim().right_aligned()),
+        );
+        f.render_widget(table, area);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::*};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    config::{key_sequence_to_string, Config, KeyBindings},
+    event::Event,
+    mode::Mode,
+};
+
+/// An action and every key sequence bound to it.
+#[derive(Debug, PartialEq, Eq)]
+struct Binding {
+    keys: Vec<String>,
+    action: String,
+    /// Bound under `Global` rather than the mode itself.
+    global: bool,
+}
+
+/// The bindings in effect in `mode`: its own first, then the global ones it doesn't override.
+fn bindings(keybindings: &KeyBindings, mode: Mode) -> Vec<Binding> {
+    let own = keybindings.get(&mode);
+    let mut bindings = Vec::new();
+    for (section, global) in [(mode, false), (Mode::Global, true)] {
+        let Some(section) = keybindings.get(&section) else {
+            continue;
+        };
+        let mut by_action: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (keys, action) in section {
+            if global && own.is_some_and(|own| own.contains_key(keys)) {
+                continue;
+            }
+            by_action.entry(format!("{:?}", action)).or_default().push(key_sequence_to_string(keys));
+        }
+        bindings.extend(by_action.into_iter().map(|(action, mut keys)| {
+            keys.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+            Binding { keys, action, global }
+        }));
+    }
+    bindings
+}
+
+/// A modal listing the key bindings of the current mode, read from the config so that it always matches.
+#[derive(Default)]
+pub struct Help {
+    config: Config,
+    mode: Mode,
+    open: bool,
+    table_state: TableState,
+}
+
+impl Help {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// While open, the help takes every key so that the screen underneath doesn't react to them.
+    fn set_open(&mut self, open: bool) -> Option<Action> {
+        self.open = open;
+        self.table_state.select(open.then_some(0));
+        Some(Action::CaptureKeys(open))
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        match self.config.keybindings.action(self.mode, &[key])? {
+            Action::Help | Action::Escape => return self.set_open(false),
+            Action::Up => self.table_state.select_previous(),
+            Action::Down => self.table_state.select_next(),
+            Action::PageUp => self.table_state.scroll_up_by(10),
+            Action::PageDown => self.table_state.scroll_down_by(10),
+            action @ (Action::Quit | Action::Suspend) => return Some(action.clone()),
+            _ => {},
+        }
+        None
+    }
+}
+
+impl Component for Help {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        Ok(match event {
+            Some(Event::Key(key)) if self.open => self.handle_key(key),
+            _ => None,
+        })
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        Ok(match action {
+            Action::SwitchMode(mode) => {
+                self.mode = mode;
+                None
+            },
+            Action::Help if !self.open => self.set_open(true),
+            _ => None,
+        })
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if !self.open {
+            return Ok(());
+        }
+        let area = Layout::default().constraints([Constraint::Percentage(100)]).margin(4).split(area)[0];
+        f.render_widget(Clear, area);
+        let rows = bindings(&self.config.keybindings, self.mode).into_iter().map(|b| {
+            let row = Row::new(vec![
+                Cell::from(b.keys.join(", ")),
+                Cell::from(b.action),
+                Cell::from(if b.global { "Global".to_string() } else { format!("{:?}", self.mode) }),
+            ]);
+            if b.global {
+                row.dim()
+            } else {
+                row
+            }
+        });
+        let table = Table::new(rows, [Constraint::Percentage(40), Constraint::Percentage(40), Constraint::Fill(1)])
+            .header(Row::new(vec!["Keys", "Action", "Where"]).bold())
+            .block(Block::default().borders(Borders::ALL).title(format!(" Keys: {:?} ", self.mode)))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_bindings() {
+        let config: Config = json5::from_str(
+            r#"{
+                "keybindings": {
+                    "Global": { "<q>": "Quit", "<Ctrl-c>": "Quit", "<j>": "Down", "<g><g>": "Up" },
+                    "Planner": { "<j>": "Sell" },
+                },
+            }"#,
+        )
+        .unwrap();
+        let binding = |keys: &[&str], action: &str, global| {
+            Binding { keys: keys.iter().map(|k| k.to_string()).collect(), action: action.to_string(), global }
+        };
+        assert_eq!(bindings(&config.keybindings, Mode::Planner), vec![
+            binding(&["<j>"], "Sell", false),
+            binding(&["<q>", "<ctrl-c>"], "Quit", true),
+            binding(&["<g><g>"], "Up", true),
+        ]);
+        assert_eq!(bindings(&config.keybindings, Mode::Home)[0], binding(&["<j>"], "Down", true));
+    }
+}
@@ -0,0 +1,222 @@
+use color_eyre::eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use thousands::Separable;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::{action::Action, api::ManagerHistory, config::Config, event::Event, mode::Mode};
+
+/// Season so far for the manager: gameweek by gameweek, past seasons and chips.
+#[derive(Default)]
+pub struct History {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    /// The manager whose history is wanted; any other's that turns up late is dropped.
+    manager_id: i64,
+    history: Option<ManagerHistory>,
+    requested: bool,
+    table_state: TableState,
+}
+
+fn money(tenths: i64) -> String {
+    format!("£{:.1}m", tenths as f64 / 10.0)
+}
+
+impl History {
+    pub fn new(manager_id: i64) -> Self {
+        Self { manager_id, ..Self::default() }
+    }
+
+    /// Forget the history shown and get `manager_id`'s instead, if it's someone else.
+    fn set_manager(&mut self, manager_id: i64) -> Option<Action> {
+        if manager_id == self.manager_id {
+            return None;
+        }
+        self.manager_id = manager_id;
+        self.history = None;
+        self.requested = false;
+        match self.mode {
+            Mode::History => self.request(),
+            _ => None,
+        }
+    }
+
+    fn request(&mut self) -> Option<Action> {
+        if self.history.is_some() || self.requested {
+            return None;
+        }
+        self.requested = true;
+        Some(Action::LoadHistory)
+    }
+
+    fn draw_gameweeks(&mut self, f: &mut Frame<'_>, area: Rect, history: &ManagerHistory) {
+        let header = Row::new(["GW", "Pts", "Bench", "Overall Rank", "Value", "Bank", "TM", "Hits"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = history.current.iter().map(|gw| {
+            Row::new(vec![
+                gw.event.to_string(),
+                gw.points.to_string(),
+                gw.points_on_bench.to_string(),
+                gw.overall_rank.separate_with_commas(),
+                money(gw.value),
+                money(gw.bank),
+                gw.event_transfers.to_string(),
+                match gw.event_transfers_cost {
+                    0 => "-".to_string(),
+                    cost => format!("-{}", cost),
+                },
+            ])
+        });
+        let table = Table::new(rows, [
+            Constraint::Length(3),
+            Constraint::Length(4),
+            Constraint::Length(5),
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(3),
+            Constraint::Length(4),
+        ])
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(" Gameweeks "));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_rank_chart(&self, f: &mut Frame<'_>, area: Rect, history: &ManagerHistory) {
+        // Plot negated ranks so that climbing the table draws the line upwards
+        let points: Vec<(f64, f64)> =
+            history.current.iter().map(|gw| (gw.event as f64, -(gw.overall_rank as f64))).collect();
+        let best = history.current.iter().map(|gw| gw.overall_rank).min().unwrap_or(1);
+        let worst = history.current.iter().map(|gw| gw.overall_rank).max().unwrap_or(1).max(best + 1);
+        let last_gw = history.current.iter().map(|gw| gw.event).max().unwrap_or(1).max(2);
+        let dataset = Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Indexed(127_u8)))
+            .data(&points);
+        let chart = Chart::new(vec![dataset])
+            .block(Block::default().borders(Borders::ALL).title(" Overall Rank "))
+            .x_axis(
+                Axis::default()
+                    .bounds([1.0, last_gw as f64])
+                    .labels(["GW1".to_string(), format!("GW{}", last_gw)])
+                    .style(Style::default().dim()),
+            )
+            .y_axis(
+                Axis::default()
+                    .bounds([-(worst as f64), -(best as f64)])
+                    .labels([
+                        worst.separate_with_commas(),
+                        ((worst + best) / 2).separate_with_commas(),
+                        best.separate_with_commas(),
+                    ])
+                    .style(Style::default().dim()),
+            );
+        f.render_widget(chart, area);
+    }
+
+    fn draw_seasons_and_chips(&self, f: &mut Frame<'_>, area: Rect, history: &ManagerHistory) {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+        let seasons: Vec<ListItem> = history
+            .past
+            .iter()
+            .rev()
+            .map(|s| {
+                ListItem::new(format!(
+                    "{}  {} pts  rank {}",
+                    s.season_name,
+                    s.total_points,
+                    s.rank.separate_with_commas()
+                ))
+            })
+            .collect();
+        f.render_widget(
+            List::new(seasons).block(Block::default().borders(Borders::ALL).title(" Past Seasons ")),
+            layout[0],
+        );
+        let chips: Vec<ListItem> =
+            history.chips.iter().map(|c| ListItem::new(format!("GW{}  {}", c.event, c.name))).collect();
+        f.render_widget(List::new(chips).block(Block::default().borders(Borders::ALL).title(" Chips ")), layout[1]);
+    }
+}
+
+impl Component for History {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::ManagerHistory(manager_id, history)) if manager_id == self.manager_id => {
+                self.requested = false;
+                self.history = Some(*history);
+                None
+            },
+            Some(Event::ProfileSwitched(_, profile)) => self.set_manager(profile.manager_id),
+            Some(Event::Refreshed(snapshot)) => self.set_manager(snapshot.manager.id),
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SwitchMode(mode) => {
+                self.mode = mode;
+                if mode == Mode::History {
+                    return Ok(self.request());
+                }
+            },
+            Action::Refresh | Action::ViewManager(_) | Action::ViewOwnTeam => {
+                self.history = None;
+                if self.mode == Mode::History {
+                    return Ok(self.request());
+                }
+            },
+            Action::RequestFailed(request, _) if *request == Action::LoadHistory => self.requested = false,
+            Action::Escape if self.mode == Mode::History => return Ok(Some(Action::SwitchMode(Mode::Home))),
+            Action::Up if self.mode == Mode::History => self.table_state.select_previous(),
+            Action::Down if self.mode == Mode::History => self.table_state.select_next(),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::History {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        let Some(history) = self.history.take() else {
+            let p = Paragraph::new("Loading season history…")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title(" History "));
+            f.render_widget(p, area);
+            return Ok(());
+        };
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(60), Constraint::Min(20)])
+            .split(area);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(layout[1]);
+        self.draw_gameweeks(f, layout[0], &history);
+        self.draw_rank_chart(f, right[0], &history);
+        self.draw_seasons_and_chips(f, right[1], &history);
+        self.history = Some(history);
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
-use fpl_api::fixture::Fixtures;
+use fpl_api::{fixture::Fixtures, manager::Pick, players::Element as LiveElement};
 use image::DynamicImage;
 use ratatui::{layout::Flex, prelude::*, widgets::*};
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
@@ -13,36 +16,76 @@ use tui_big_text::{BigTextBuilder, PixelSize};
 use super::{manager_summary, Component, Frame};
 use crate::{
     action::Action,
-    components::{manager_summary::ManagerSummary, player_card::PlayerCard, players::Players},
+    components::{
+        manager_summary::ManagerSummary,
+        player_card::{PlayerCard, Substitution},
+        players::Players,
+    },
     config::{Config, KeyBindings},
+    data_source::Snapshot,
     event::Event,
+    lineup,
+    mode::Mode,
+    points,
+    price_history::PriceHistory,
+    price_predictor,
+    projection::Projection,
+    utils::format_utc_time,
 };
 
 pub struct Home {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
+    mode: Mode,
 
     // TODO: do i need this? can just keep a vector of players
-    picked_players: [Players; 5],
+    picked_players: [Players; 6],
     player_code_to_player: HashMap<i64, (usize, usize)>,
     manager_summary: ManagerSummary,
     fixtures: Fixtures,
+    projection: Projection,
+    prices: Arc<PriceHistory>,
+    /// Managers in the game, which price changes are predicted against.
+    total_players: i64,
+    player_id_to_details: HashMap<i64, fpl_api::bootstrap::Element>,
+    team_id_to_details: HashMap<i64, fpl_api::bootstrap::Team>,
+    manager: fpl_api::manager::Manager,
+    /// Id of the manager the app was started for, to tell when we're looking at a rival's team.
+    own_manager_id: i64,
+    /// Name of the config profile in use, if any.
+    profile: Option<String>,
+    /// The gameweek on the pitch, which can differ from `manager.current_event` while browsing.
+    gameweek: i64,
+    gw_teams: HashMap<i64, fpl_api::manager::GWTeam>,
+    live: HashMap<i64, fpl_api::players::GWLiveData>,
+    requested_gameweeks: HashSet<i64>,
+    /// Local lineup edits for a gameweek, which are never sent anywhere.
+    what_if: Option<(i64, Vec<Pick>)>,
+    picker: Option<Picker>,
+    team_to_badge: HashMap<i64, DynamicImage>,
 
     // UI state
     active_player_coordinate: (usize, usize),
     show_player_big: bool,
+    /// Showing the best lineup for the next gameweek next to the current picks.
+    show_suggestion: bool,
+    /// Element id of the player marked to be swapped.
+    swap_from: Option<i64>,
+    stale: bool,
+    last_error: Option<String>,
+    refreshing: bool,
+    last_updated: Option<SystemTime>,
 }
 
 impl Home {
     fn organise_players(
-        gw_picks: fpl_api::manager::GWTeam,
-        player_id_to_details: HashMap<i64, fpl_api::bootstrap::Element>,
-        team_id_to_details: HashMap<i64, fpl_api::bootstrap::Team>,
+        picks: &[Pick],
+        player_id_to_details: &HashMap<i64, fpl_api::bootstrap::Element>,
+        team_id_to_details: &HashMap<i64, fpl_api::bootstrap::Team>,
         mut picker: Option<Picker>,
-        team_to_badge: HashMap<i64, DynamicImage>,
-    ) -> [Players; 5] {
-        let mut picked_player_cards: Vec<PlayerCard> = gw_picks
-            .picks
+        team_to_badge: &HashMap<i64, DynamicImage>,
+    ) -> [Players; 6] {
+        let mut picked_player_cards: Vec<PlayerCard> = picks
             .iter()
             .map(|p| {
                 let player_detail = player_id_to_details.get(&p.element).unwrap(); // TODO: handle. but should never happen
@@ -57,7 +100,7 @@ impl Home {
                             team_to_badge.get(&player_detail.team_code).map(|d| p.new_resize_protocol(d.clone()))
                         },
                     },
-                    p.position,
+                    p.clone(),
                 )
             })
             .collect();
@@ -68,6 +111,7 @@ impl Home {
             Players::new("Midfielders".to_string(), Vec::new()),
             Players::new("Forwards".to_string(), Vec::new()),
             Players::new("Bench".to_string(), Vec::new()),
+            Players::new("Assistant Manager".to_string(), Vec::new()),
         ];
 
         while let Some(pc) = picked_player_cards.pop() {
@@ -88,94 +132,495 @@ impl Home {
                 (_, 4) => {
                     organised_players[3].players.push(pc);
                 },
-                _ => (),
+                (_, lineup::ASSISTANT_MANAGER) => {
+                    organised_players[5].players.push(pc);
+                },
+                (_, element_type) => {
+                    // Keep them visible rather than losing them from the squad
+                    log::warn!(
+                        "Unknown element_type {} for {}, showing them on the bench",
+                        element_type,
+                        pc.details.web_name
+                    );
+                    organised_players[4].players.push(pc);
+                },
             };
         }
         organised_players
     }
 
-    pub fn new(
-        manager: fpl_api::manager::Manager,
-        bootstrap_data: fpl_api::bootstrap::BootstrapData,
-        gw_picks: fpl_api::manager::GWTeam,
-        fixtures: Fixtures,
-        picker: Option<Picker>,
-        team_to_badge: HashMap<i64, DynamicImage>,
-    ) -> Self {
-        let player_id_to_details: HashMap<i64, fpl_api::bootstrap::Element> =
-            bootstrap_data.elements.iter().fold(HashMap::new(), |mut m, p| {
-                m.insert(p.id, p.clone());
-                m
-            });
-        let team_id_to_details: HashMap<i64, fpl_api::bootstrap::Team> =
-            bootstrap_data.teams.iter().fold(HashMap::new(), |mut m, p| {
-                m.insert(p.id, p.clone());
-                m
-            });
-        let organised_players =
-            Self::organise_players(gw_picks, player_id_to_details, team_id_to_details, picker, team_to_badge);
-        let mut player_code_to_player = HashMap::new();
-        for (i, p) in organised_players.iter().enumerate() {
-            for (j, pc) in p.players.iter().enumerate() {
-                player_code_to_player.insert(pc.details.code, (i, j));
-            }
-        }
-        let active_player_coordinate = (0, 0);
-        Home {
+    pub fn new(snapshot: Snapshot, picker: Option<Picker>, team_to_badge: HashMap<i64, DynamicImage>) -> Self {
+        let mut home = Home {
             command_tx: None,
             config: Default::default(),
-            picked_players: organised_players,
-            player_code_to_player,
-            manager_summary: ManagerSummary::new(manager),
-            fixtures,
-            active_player_coordinate,
+            mode: Mode::Home,
+            picked_players: Default::default(),
+            player_code_to_player: HashMap::new(),
+            manager_summary: ManagerSummary::new(snapshot.manager.clone()),
+            fixtures: Vec::new(),
+            projection: Projection::default(),
+            prices: Arc::default(),
+            total_players: 0,
+            player_id_to_details: HashMap::new(),
+            team_id_to_details: HashMap::new(),
+            manager: snapshot.manager.clone(),
+            own_manager_id: snapshot.manager.id,
+            profile: None,
+            gameweek: snapshot.gw_picks.entry_history.event,
+            gw_teams: HashMap::new(),
+            live: HashMap::new(),
+            requested_gameweeks: HashSet::new(),
+            what_if: None,
+            picker,
+            team_to_badge,
+            active_player_coordinate: (0, 0),
             show_player_big: false,
-        }
+            show_suggestion: false,
+            swap_from: None,
+            stale: false,
+            last_error: None,
+            refreshing: false,
+            last_updated: None,
+        };
+        home.load(snapshot);
+        home
     }
 
-    fn mark_player_active_state(&mut self, coordinate: (usize, usize), state: bool) {
-        if let Some(x) = self.picked_players[coordinate.0].players.get_mut(coordinate.1) {
-            x.mark_active(state)
+    /// Swap in freshly loaded data, keeping the selected player and any open card.
+    pub fn load(&mut self, snapshot: Snapshot) {
+        self.player_id_to_details = snapshot.bootstrap.elements.iter().fold(HashMap::new(), |mut m, p| {
+            m.insert(p.id, p.clone());
+            m
+        });
+        self.team_id_to_details = snapshot.bootstrap.teams.iter().fold(HashMap::new(), |mut m, p| {
+            m.insert(p.id, p.clone());
+            m
+        });
+        if snapshot.manager.id != self.manager.id {
+            // A different manager's team: none of what we have for other gameweeks applies
+            self.gw_teams.clear();
+            self.requested_gameweeks.clear();
+            self.what_if = None;
+            self.gameweek = snapshot.gw_picks.entry_history.event;
         }
+        self.projection =
+            Projection::new(&snapshot.bootstrap, &snapshot.fixtures, self.config.config.projection_gameweeks);
+        self.total_players = snapshot.bootstrap.total_players;
+        self.manager = snapshot.manager;
+        self.fixtures = snapshot.fixtures;
+        self.stale = snapshot.stale;
+        self.refreshing = false;
+        self.last_updated = Some(SystemTime::now());
+        self.gw_teams.insert(snapshot.gw_picks.entry_history.event, snapshot.gw_picks);
+        self.show_gameweek(self.gameweek);
     }
 
-    fn update_player_active(&mut self, old: (usize, usize)) {
-        // TODO: this is  becoming a bit gnarly. would it not be better to just
-        // have one state and draw that, this component thing is getting complicated
-        self.mark_player_active_state(old, false);
+    /// Put the squad for `gameweek` on the pitch, asking for it to be loaded if we don't have it yet.
+    fn show_gameweek(&mut self, gameweek: i64) {
+        self.gameweek = gameweek;
+        self.swap_from = None;
+        self.request_missing_gameweek();
+        let Some(gw_team) = self.gw_teams.get(&gameweek) else {
+            return;
+        };
+        self.picked_players = Self::organise_players(
+            self.picks(),
+            &self.player_id_to_details,
+            &self.team_id_to_details,
+            self.picker,
+            &self.team_to_badge,
+        );
+        self.player_code_to_player.clear();
+        for (i, p) in self.picked_players.iter().enumerate() {
+            for (j, pc) in p.players.iter().enumerate() {
+                self.player_code_to_player.insert(pc.details.code, (i, j));
+            }
+        }
+        self.manager_summary = ManagerSummary::new(self.manager.clone());
+        self.manager_summary.set_gameweek(gameweek == self.manager.current_event, gw_team);
+
+        if self.active_player_coordinate.0 == 5 && self.picked_players[5].players.is_empty() {
+            self.active_player_coordinate = (4, 0);
+        }
+        let (row, col) = self.active_player_coordinate;
+        let row_len = self.picked_players[row].players.len();
+        self.active_player_coordinate.1 = col.min(row_len.saturating_sub(1));
+        if row_len == 0 {
+            self.show_player_big = false;
+        }
         self.mark_player_active_state(self.active_player_coordinate, true);
+        self.apply_live_data();
+        self.apply_projection();
+        self.apply_prices();
+        self.request_photos();
     }
-}
 
-impl Component for Home {
-    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
-        self.command_tx = Some(tx);
-        Ok(())
+    /// Picks on the pitch: the what-if lineup if there is one for this gameweek, else the real picks.
+    fn picks(&self) -> &[Pick] {
+        match &self.what_if {
+            Some((gameweek, picks)) if *gameweek == self.gameweek => picks,
+            _ => self.gw_teams.get(&self.gameweek).map_or(&[], |t| t.picks.as_slice()),
+        }
     }
 
-    fn register_config_handler(&mut self, config: Config) -> Result<()> {
-        self.config = config;
-        Ok(())
+    fn selected_element(&self) -> Option<i64> {
+        let (row, col) = self.active_player_coordinate;
+        self.picked_players[row].players.get(col).map(|p| p.details.id)
     }
 
-    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
-        let r = match event {
-            Some(Event::Key(key_event)) => self.handle_key_events(key_event)?,
-            Some(Event::Mouse(mouse_event)) => self.handle_mouse_events(mouse_event)?,
-            Some(Event::PlayerImage(pc, image)) => {
-                if let Some(cord) = self.player_code_to_player.get(&pc) {
-                    self.picked_players[cord.0].players.get_mut(cord.1).unwrap().set_image(image.clone());
+    fn apply_projection(&mut self) {
+        for card in self.picked_players.iter_mut().flat_map(|p| p.players.iter_mut()) {
+            card.set_projection(self.projection.upcoming(card.details.id));
+        }
+    }
+
+    fn apply_prices(&mut self) {
+        let cards = self.picked_players.iter().flat_map(|p| p.players.iter());
+        let predictions = price_predictor::predict(cards.map(|c| &c.details), self.total_players, &self.prices);
+        for card in self.picked_players.iter_mut().flat_map(|p| p.players.iter_mut()) {
+            card.set_price_change(self.prices.since_last_run(card.details.id, card.details.now_cost));
+            card.set_price_prediction(predictions.get(&card.details.id).copied());
+        }
+    }
+
+    /// Mark the selected player for a swap, or swap them with the one already marked.
+    fn mark_swap(&mut self) {
+        let Some(element) = self.selected_element() else {
+            return;
+        };
+        let Some(from) = self.swap_from.take() else {
+            self.swap_from = Some(element);
+            self.mark_swap_state();
+            return;
+        };
+        if from == element {
+            self.mark_swap_state();
+            return;
+        }
+        match lineup::swap(self.picks(), from, element, |e| self.element_type(e)) {
+            Ok(picks) => {
+                self.last_error = None;
+                self.what_if = Some((self.gameweek, picks));
+                // Follow the player that was selected to wherever they ended up
+                self.show_gameweek(self.gameweek);
+                if let Some(&(row, col)) =
+                    self.player_id_to_details.get(&element).and_then(|d| self.player_code_to_player.get(&d.code))
+                {
+                    let old = self.active_player_coordinate;
+                    self.active_player_coordinate = (row, col);
+                    self.update_player_active(old);
                 }
-                None
+            },
+            Err(e) => {
+                self.last_error = Some(e);
+                self.mark_swap_state();
+            },
+        }
+    }
+
+    fn mark_swap_state(&mut self) {
+        for card in self.picked_players.iter_mut().flat_map(|p| p.players.iter_mut()) {
+            card.mark_swap(Some(card.details.id) == self.swap_from);
+        }
+    }
+
+    /// The best lineup for the next gameweek, as `(event, picks)`, when the squad on the pitch is the latest.
+    fn suggestion(&self) -> Option<(i64, Vec<Pick>)> {
+        if self.gameweek != self.manager.current_event {
+            return None;
+        }
+        let picks = &self.gw_teams.get(&self.gameweek)?.picks;
+        let event = self.projection.first;
+        let lineup = lineup::best_lineup(picks, |e| self.projection.gameweek(e, event), |e| self.element_type(e));
+        Some((event, lineup))
+    }
+
+    fn toggle_suggestion(&mut self) {
+        if !self.show_suggestion && self.suggestion().is_none() {
+            self.last_error = Some("Suggestions are for the latest gameweek's squad".to_string());
+            return;
+        }
+        self.show_suggestion = !self.show_suggestion;
+    }
+
+    /// Put the suggested lineup on the pitch as the what-if lineup.
+    fn apply_suggestion(&mut self) {
+        let Some((_, picks)) = self.suggestion() else {
+            self.last_error = Some("Suggestions are for the latest gameweek's squad".to_string());
+            return;
+        };
+        self.last_error = None;
+        self.show_suggestion = false;
+        self.what_if = Some((self.gameweek, picks));
+        self.show_gameweek(self.gameweek);
+    }
+
+    /// How the suggested lineup differs from the real picks.
+    fn suggestion_lines(&self, event: i64, suggested: &[Pick]) -> Vec<Line<'static>> {
+        let current = self.gw_teams.get(&self.gameweek).map_or(&[][..], |t| t.picks.as_slice());
+        let projected = |picks: &[Pick]| -> f64 {
+            picks
+                .iter()
+                .filter(|p| lineup::is_starter(p))
+                .map(|p| self.projection.gameweek(p.element, event) * p.multiplier as f64)
+                .sum()
+        };
+        let (best, now) = (projected(suggested), projected(current));
+        let mut lines = vec![Line::from(format!("Projected {:.1} pts, {:+.1} on your picks", best, best - now)).bold()];
+        lines.push(Line::default());
+
+        let starts = |picks: &[Pick], element: i64| picks.iter().any(|p| p.element == element && lineup::is_starter(p));
+        let mut changed = false;
+        for p in suggested.iter().filter(|p| lineup::is_starter(p) && !starts(current, p.element)) {
+            lines.push(Line::styled(format!("▲ {} starts", self.web_name(p.element)), Color::Green));
+            changed = true;
+        }
+        for p in current.iter().filter(|p| lineup::is_starter(p) && !starts(suggested, p.element)) {
+            lines.push(Line::styled(format!("▼ {} to the bench", self.web_name(p.element)), Color::Red));
+            changed = true;
+        }
+        let armband = |picks: &[Pick], vice: bool| {
+            picks.iter().find(|p| if vice { p.is_vice_captain } else { p.is_captain }).map(|p| p.element)
+        };
+        for (label, vice) in [("Captain", false), ("Vice-captain", true)] {
+            let (was, now) = (armband(current, vice), armband(suggested, vice));
+            let name = |element: Option<i64>| element.map_or_else(|| "-".to_string(), |e| self.web_name(e));
+            if was != now {
+                lines.push(Line::from(format!("{}: {} → {}", label, name(was), name(now))));
+                changed = true;
+            }
+        }
+        let bench = |picks: &[Pick]| {
+            let mut bench: Vec<&Pick> = picks.iter().filter(|p| lineup::is_bench(p)).collect();
+            bench.sort_by_key(|p| p.position);
+            bench.iter().map(|p| self.web_name(p.element)).collect::<Vec<_>>().join(", ")
+        };
+        if bench(current) != bench(suggested) {
+            lines.push(Line::from(format!("Bench: {}", bench(suggested))));
+            lines.push(Line::from(format!("  was: {}", bench(current))).dim());
+            changed = true;
+        }
+        if !changed {
+            lines.push(Line::from(format!("Your lineup is already the best one for GW{}", event)));
+        }
+        lines
+    }
+
+    fn discard_what_if(&mut self) {
+        if self.what_if.take().is_some() {
+            self.show_gameweek(self.gameweek);
+        }
+    }
+
+    /// Projected total for a set of picks: live points where the gameweek has them, otherwise
+    /// the FPL expected points for this gameweek.
+    fn projected_total(&self, picks: &[Pick], live: Option<&HashMap<i64, LiveElement>>) -> f64 {
+        picks
+            .iter()
+            .map(|p| {
+                let points = match live {
+                    Some(live) => live.get(&p.element).map_or(0.0, |e| e.stats.total_points as f64),
+                    None => {
+                        self.player_id_to_details.get(&p.element).and_then(|e| e.ep_this.parse().ok()).unwrap_or(0.0)
+                    },
+                };
+                p.multiplier as f64 * points
+            })
+            .sum()
+    }
+
+    /// Ask for the picks and points of the gameweek on the pitch unless we already have them.
+    ///
+    /// Live points for the current gameweek arrive on their own with each live refresh.
+    fn request_missing_gameweek(&mut self) {
+        let gameweek = self.gameweek;
+        let missing_live = gameweek != self.manager.current_event && !self.live.contains_key(&gameweek);
+        if !missing_live && self.gw_teams.contains_key(&gameweek) {
+            return;
+        }
+        if let Some(tx) = &self.command_tx {
+            if self.requested_gameweeks.insert(gameweek) {
+                let _ = tx.send(Action::LoadGameweek(gameweek));
+            }
+        }
+    }
+
+    fn step_gameweek(&mut self, delta: i64) {
+        let first = self.manager.started_event.max(1);
+        let target = (self.gameweek + delta).clamp(first, self.manager.current_event.max(first));
+        if target != self.gameweek {
+            self.show_gameweek(target);
+        }
+    }
+
+    /// Warm the photo cache for the whole squad so cards open with their photo.
+    fn request_photos(&self) {
+        if let Some(tx) = &self.command_tx {
+            for p in self.picked_players.iter().flat_map(|p| p.players.iter()) {
+                let _ = tx.send(Action::GetPlayerImage(p.details.code));
+            }
+        }
+    }
+
+    /// Hints for the keys currently bound to `entries`, see [`KeyBindings::hints`].
+    fn hints(&self, entries: &[(&[Action], &str)]) -> String {
+        self.config.keybindings.hints(Mode::Home, entries)
+    }
+
+    fn status_line(&self) -> Line<'_> {
+        let mut spans = Vec::new();
+        if let Some(e) = &self.last_error {
+            spans.push(Span::styled(format!(" {} ", e), Style::default().fg(Color::Red)));
+        }
+        if self.manager.id != self.own_manager_id {
+            spans.push(Span::styled(
+                format!(" viewing {} ·{}", self.manager.name, self.hints(&[(&[Action::ViewOwnTeam], "own team")])),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        if self.what_if.as_ref().is_some_and(|(gameweek, _)| *gameweek == self.gameweek) {
+            let hint = format!(" what-if lineup ·{}", self.hints(&[(&[Action::Discard], "discard")]));
+            spans.push(Span::styled(hint, Style::default().fg(Color::Yellow)));
+        } else if self.swap_from.is_some() {
+            spans.push(Span::raw(self.hints(&[(&[Action::Swap], "swap with…")])));
+        }
+        if let Some(profile) = &self.profile {
+            spans.push(
+                Span::raw(format!(" profile {} ·{}", profile, self.hints(&[(&[Action::NextProfile], "switch")]))).dim(),
+            );
+        }
+        if self.stale {
+            spans.push(Span::styled(" ⚠ stale: showing cached data ", Style::default().fg(Color::Yellow)));
+        }
+        if self.requested_gameweeks.contains(&self.gameweek) {
+            spans.push(Span::raw(format!(" loading GW{}… ", self.gameweek)));
+        }
+        if self.refreshing {
+            spans.push(Span::raw(" refreshing… "));
+        } else if let Some(updated) = self.last_updated {
+            spans.push(Span::raw(format!(" updated {} ", format_utc_time(updated))).dim());
+        }
+        Line::from(spans).right_aligned()
+    }
+
+    fn gameweek_title(&self) -> Line<'_> {
+        let mut title = format!(" GW{} ", self.gameweek);
+        if let Some(chip) = self.gw_teams.get(&self.gameweek).and_then(|t| t.active_chip.as_ref()) {
+            title.push_str(&format!("· {} ", chip));
+        }
+        let keys = |action| self.config.keybindings.keys(Mode::Home, &action).unwrap_or_default();
+        Line::from(vec![
+            Span::raw(format!(" ◀ {} ", keys(Action::PreviousGameweek))).dim(),
+            Span::raw(title).bold(),
+            Span::raw(format!("{} ▶ ", keys(Action::NextGameweek))).dim(),
+        ])
+        .centered()
+    }
+
+    /// Whether a player has no minutes and no fixture left to get some in the gameweek on the pitch.
+    fn element_type(&self, element: i64) -> i64 {
+        self.player_id_to_details.get(&element).map_or(0, |e| e.element_type)
+    }
+
+    fn web_name(&self, element: i64) -> String {
+        self.player_id_to_details.get(&element).map_or_else(|| element.to_string(), |e| e.web_name.clone())
+    }
+
+    fn apply_live_data(&mut self) {
+        let current = self.gameweek == self.manager.current_event;
+        let live = self.live.get(&self.gameweek).map(points::index_live);
+        for card in self.picked_players.iter_mut().flat_map(|p| p.players.iter_mut()) {
+            match &live {
+                Some(live) => {
+                    let element = live.get(&card.details.id);
+                    card.set_live(element.map(|e| e.stats.clone()));
+                    card.set_explain(element.map(|e| e.explain.clone()).unwrap_or_default());
+                },
+                // Bootstrap's event points only describe the current gameweek
+                None if !current => card.set_live(Some(Default::default())),
+                None => {},
+            }
+        }
+        let Some(real_picks) = self.gw_teams.get(&self.gameweek).map(|t| t.picks.clone()) else {
+            return;
+        };
+        let effective_picks = |picks: &[Pick]| {
+            match &live {
+                Some(live) => {
+                    points::live_effective_picks(picks, self.gameweek, &self.player_id_to_details, &self.fixtures, live)
+                },
+                None => picks.to_vec(),
+            }
+        };
+        // The cards show the what-if lineup when there is one, the summary always the real team
+        let shown = self.picks().to_vec();
+        let shown_effective = effective_picks(&shown);
+        let effective = effective_picks(&real_picks);
+        for card in self.picked_players.iter_mut().flat_map(|p| p.players.iter_mut()) {
+            let before = shown.iter().find(|p| p.element == card.details.id);
+            let after = shown_effective.iter().find(|p| p.element == card.details.id);
+            let Some((before, after)) = before.zip(after) else {
+                continue;
+            };
+            card.set_multiplier(after.multiplier);
+            card.set_substitution(match (lineup::is_starter(before), lineup::is_starter(after)) {
+                (true, false) => Some(Substitution::Off),
+                (false, true) => Some(Substitution::On),
+                _ => None,
+            });
+        }
+        let mut subs_off = Vec::new();
+        let mut subs_on = Vec::new();
+        for before in &real_picks {
+            let Some(after) = effective.iter().find(|p| p.element == before.element) else {
+                continue;
+            };
+            match (lineup::is_starter(before), lineup::is_starter(after)) {
+                (true, false) => subs_off.push((before.position, self.web_name(before.element))),
+                (false, true) => subs_on.push((after.position, self.web_name(after.element))),
+                _ => {},
+            }
+        }
+        // Pair each player who went off with whoever took their place
+        subs_off.sort();
+        subs_on.sort();
+        let subs = subs_off.into_iter().zip(subs_on).map(|((_, off), (_, on))| format!("{} → {}", off, on)).collect();
+        self.manager_summary.set_auto_subs(subs);
+        let captain = real_picks.iter().find(|p| p.is_captain).map(|c| {
+            match real_picks
+                .iter()
+                .find(|p| p.is_vice_captain)
+                .filter(|v| effective.iter().any(|e| e.element == v.element && e.multiplier > 1))
+            {
+                Some(vice) => format!("{} → {} (VC)", self.web_name(c.element), self.web_name(vice.element)),
+                None => self.web_name(c.element),
+            }
+        });
+        self.manager_summary.set_captain(captain);
+        let what_if = match &self.what_if {
+            Some((gameweek, _)) if *gameweek == self.gameweek => {
+                let projected = self.projected_total(&shown_effective, live.as_ref());
+                let baseline = self.projected_total(&effective, live.as_ref());
+                Some((projected, projected - baseline))
             },
             _ => None,
         };
-        Ok(r)
+        self.manager_summary.set_what_if(what_if);
+        if let Some(live) = &live {
+            let total = points::gw_total(&effective, |element| live.get(&element).map_or(0, |e| e.stats.total_points));
+            self.manager_summary.set_live_points(total);
+        }
     }
 
-    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        match key.code {
-            KeyCode::Enter => {
+    fn mark_player_active_state(&mut self, coordinate: (usize, usize), state: bool) {
+        if let Some(x) = self.picked_players[coordinate.0].players.get_mut(coordinate.1) {
+            x.mark_active(state)
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Enter => {
                 self.show_player_big = true;
                 let coordinate = self.active_player_coordinate;
                 if let Some(current_player) = self.picked_players[coordinate.0].players.get(coordinate.1) {
@@ -189,12 +634,35 @@ impl Component for Home {
                     Ok(None)
                 }
             },
-            KeyCode::Esc => {
+            Action::Escape => {
                 self.show_player_big = false;
                 Ok(None)
             },
-
-            KeyCode::Left => {
+            Action::Swap => {
+                self.mark_swap();
+                Ok(None)
+            },
+            Action::Discard => {
+                self.discard_what_if();
+                Ok(None)
+            },
+            Action::ToggleSuggestion => {
+                self.toggle_suggestion();
+                Ok(None)
+            },
+            Action::ApplySuggestion => {
+                self.apply_suggestion();
+                Ok(None)
+            },
+            Action::PreviousGameweek => {
+                self.step_gameweek(-1);
+                Ok(None)
+            },
+            Action::NextGameweek => {
+                self.step_gameweek(1);
+                Ok(None)
+            },
+            Action::Left => {
                 let old = self.active_player_coordinate;
                 if self.active_player_coordinate.1 != 0 {
                     self.active_player_coordinate.1 -= 1;
@@ -202,7 +670,7 @@ impl Component for Home {
                 self.update_player_active(old);
                 Ok(None)
             },
-            KeyCode::Right => {
+            Action::Right => {
                 let old = self.active_player_coordinate;
                 if self.picked_players[self.active_player_coordinate.0].players.len()
                     != self.active_player_coordinate.1 + 1
@@ -212,7 +680,7 @@ impl Component for Home {
                 self.update_player_active(old);
                 Ok(None)
             },
-            KeyCode::Up => {
+            Action::Up => {
                 let old = self.active_player_coordinate;
                 self.active_player_coordinate.0 = match self.active_player_coordinate.0 {
                     0 => 0,
@@ -222,10 +690,12 @@ impl Component for Home {
                 self.update_player_active(old);
                 Ok(None)
             },
-            KeyCode::Down => {
+            Action::Down => {
                 let old = self.active_player_coordinate;
                 self.active_player_coordinate.0 = match self.active_player_coordinate.0 {
-                    3 => 3,
+                    // The assistant manager's slot is only there while the chip is active
+                    4 if !self.picked_players[5].players.is_empty() => 5,
+                    4 | 5 => self.active_player_coordinate.0,
                     _ => self.active_player_coordinate.0 + 1,
                 };
                 self.active_player_coordinate.1 = 0;
@@ -236,12 +706,107 @@ impl Component for Home {
         }
     }
 
+    fn update_player_active(&mut self, old: (usize, usize)) {
+        // TODO: this is  becoming a bit gnarly. would it not be better to just
+        // have one state and draw that, this component thing is getting complicated
+        self.mark_player_active_state(old, false);
+        self.mark_player_active_state(self.active_player_coordinate, true);
+    }
+}
+
+impl Component for Home {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.projection.set_horizon(config.config.projection_gameweeks);
+        self.config = config;
+        self.apply_projection();
+        Ok(())
+    }
+
+    fn init(&mut self, area: Size) -> Result<()> {
+        self.request_missing_gameweek();
+        self.request_photos();
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Mouse(mouse_event)) if self.mode == Mode::Home => self.handle_mouse_events(mouse_event)?,
+            Some(Event::PlayerImage(pc, image)) => {
+                if let Some(cord) = self.player_code_to_player.get(&pc) {
+                    self.picked_players[cord.0].players.get_mut(cord.1).unwrap().set_image(image.clone());
+                }
+                None
+            },
+            Some(Event::LiveData(gameweek, live)) => {
+                self.live.insert(gameweek, live);
+                if gameweek == self.gameweek {
+                    self.apply_live_data();
+                }
+                None
+            },
+            Some(Event::GameweekLoaded(gameweek, gw_team, live)) => {
+                self.requested_gameweeks.remove(&gameweek);
+                self.gw_teams.insert(gameweek, *gw_team);
+                if let Some(live) = live {
+                    self.live.insert(gameweek, live);
+                }
+                if gameweek == self.gameweek {
+                    self.show_gameweek(gameweek);
+                }
+                None
+            },
+            Some(Event::Refreshed(snapshot)) => {
+                self.load(*snapshot);
+                None
+            },
+            Some(Event::ProfileSwitched(name, profile)) => {
+                self.own_manager_id = profile.manager_id;
+                self.profile = Some(name);
+                None
+            },
+            Some(Event::PriceHistory(prices)) => {
+                self.prices = prices;
+                self.apply_prices();
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        {}
+        match action {
+            Action::SwitchMode(mode) => self.mode = mode,
+            Action::Refresh => {
+                self.refreshing = true;
+                self.last_error = None;
+            },
+            Action::Error(e) => self.last_error = Some(e),
+            Action::RequestFailed(request, e) => {
+                match *request {
+                    Action::Refresh => self.refreshing = false,
+                    Action::LoadGameweek(gameweek) => {
+                        self.requested_gameweeks.remove(&gameweek);
+                    },
+                    _ => {},
+                }
+                self.last_error = Some(e);
+            },
+            action if self.mode == Mode::Home => return self.handle_action(action),
+            _ => {},
+        }
         Ok(None)
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Home {
+            return Ok(());
+        }
         let overall_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
@@ -271,13 +836,28 @@ impl Component for Home {
                 Constraint::Length(1), // The 1 px here is becuase i stretch inside the nextedt layout
             ])
             .split(overall_layout[1]);
-        f.render_widget(Block::new().borders(Borders::ALL), overall_layout[1]);
+        f.render_widget(
+            Block::new().borders(Borders::ALL).title_top(self.gameweek_title()).title_bottom(self.status_line()),
+            overall_layout[1],
+        );
+        // The assistant manager's row is drawn beside the manager summary instead
         for i in 1..6 {
             self.picked_players[i - 1].draw(f, layouts[i])?;
         }
-        self.manager_summary.draw(f, left_layout[1])?;
+        if self.picked_players[5].players.is_empty() {
+            self.manager_summary.draw(f, left_layout[1])?;
+        } else {
+            let summary_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(10), Constraint::Percentage(30)])
+                .split(left_layout[1]);
+            self.manager_summary.draw(f, summary_layout[0])?;
+            for card in self.picked_players[5].players.iter_mut() {
+                card.draw(f, summary_layout[1])?;
+            }
+        }
 
-        if self.show_player_big {
+        if self.show_player_big && !self.picked_players[self.active_player_coordinate.0].players.is_empty() {
             let card_layout =
                 Layout::default().constraints([Constraint::Percentage(100)]).margin(4).split(overall_layout[1])[0];
             self.picked_players[self.active_player_coordinate.0]
@@ -286,6 +866,24 @@ impl Component for Home {
                 .unwrap()
                 .draw_big(f, card_layout)?;
         }
+        if let Some((event, suggested)) = self.suggestion().filter(|_| self.show_suggestion) {
+            let area =
+                Layout::default().constraints([Constraint::Percentage(100)]).margin(6).split(overall_layout[1])[0];
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_set(symbols::border::DOUBLE)
+                .title(format!(" Best lineup for GW{} ", event))
+                .title_bottom(
+                    Line::from(self.hints(&[
+                        (&[Action::ApplySuggestion], "apply as what-if"),
+                        (&[Action::ToggleSuggestion], "close"),
+                    ]))
+                    .dim()
+                    .right_aligned(),
+                );
+            f.render_widget(Clear, area);
+            f.render_widget(Paragraph::new(self.suggestion_lines(event, &suggested)).block(block), area);
+        }
         Ok(())
     }
 }
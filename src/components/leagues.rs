@@ -0,0 +1,395 @@
+use std::collections::{HashMap, HashSet};
+
+use color_eyre::eyre::Result;
+use fpl_api::manager::Manager;
+use ratatui::{prelude::*, widgets::*};
+use thousands::Separable;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{h2h::H2H, Component, Frame};
+use crate::{
+    action::Action,
+    api::{ClassicStandings, H2HLeague},
+    config::{Config, Profile},
+    data_source::Snapshot,
+    event::Event,
+    mode::Mode,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Classic,
+    H2H,
+    /// A manager from the profile's rivals rather than a league.
+    Rival,
+}
+
+/// A row in the list of the manager's leagues.
+struct LeagueRow {
+    kind: Kind,
+    id: i64,
+    name: String,
+    entry_rank: i64,
+    entry_last_rank: i64,
+    favourite: bool,
+}
+
+impl LeagueRow {
+    fn new(kind: Kind, id: i64, name: String) -> Self {
+        Self { kind, id, name, entry_rank: 0, entry_last_rank: 0, favourite: false }
+    }
+}
+
+/// The manager's leagues with the profile's favourites first, followed by the profile's rivals.
+fn league_rows(manager: &Manager, profile: &Profile) -> Vec<LeagueRow> {
+    let classic = manager.leagues.classic.iter().map(|l| {
+        LeagueRow {
+            entry_rank: l.entry_rank,
+            entry_last_rank: l.entry_last_rank,
+            ..LeagueRow::new(Kind::Classic, l.id, l.name.clone())
+        }
+    });
+    let h2h = H2HLeague::from_manager(manager).into_iter().map(|l| {
+        LeagueRow {
+            entry_rank: l.entry_rank,
+            entry_last_rank: l.entry_last_rank,
+            ..LeagueRow::new(Kind::H2H, l.id, l.name)
+        }
+    });
+    let mut rows: Vec<LeagueRow> = classic.chain(h2h).collect();
+    for &id in &profile.leagues {
+        if !rows.iter().any(|r| r.id == id) {
+            let kind = if profile.h2h_leagues.contains(&id) { Kind::H2H } else { Kind::Classic };
+            rows.push(LeagueRow::new(kind, id, format!("League {}", id)));
+        }
+    }
+    for row in rows.iter_mut() {
+        row.favourite = profile.leagues.contains(&row.id);
+    }
+    rows.sort_by_key(|r| profile.leagues.iter().position(|id| *id == r.id).unwrap_or(usize::MAX));
+    rows.extend(profile.rivals.iter().map(|&id| LeagueRow::new(Kind::Rival, id, format!("Manager {}", id))));
+    rows
+}
+
+/// The manager's mini-leagues, and the standings of whichever one is open.
+pub struct Leagues {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    /// Leagues always belong to the manager the app was started with, even while viewing a rival.
+    own_manager_id: i64,
+    /// The own manager's details, once loaded.
+    manager: Manager,
+    profile: Profile,
+    leagues: Vec<LeagueRow>,
+    list_state: ListState,
+    /// The open classic league and page, if any.
+    open: Option<(i64, i64)>,
+    pages: HashMap<(i64, i64), ClassicStandings>,
+    requested: HashSet<(i64, i64)>,
+    table_state: TableState,
+    h2h: H2H,
+}
+
+/// Arrow showing how a rank moved since the previous gameweek.
+fn movement(rank: i64, last_rank: i64) -> Span<'static> {
+    if last_rank == 0 || rank == last_rank {
+        Span::raw("-").dim()
+    } else if rank < last_rank {
+        Span::styled("▲", Style::default().fg(Color::Green))
+    } else {
+        Span::styled("▼", Style::default().fg(Color::Red))
+    }
+}
+
+impl Leagues {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let manager = &snapshot.manager;
+        let leagues = league_rows(manager, &Profile::default());
+        let mut list_state = ListState::default();
+        if !leagues.is_empty() {
+            list_state.select(Some(0));
+        }
+        Self {
+            command_tx: None,
+            config: Config::default(),
+            mode: Mode::Home,
+            own_manager_id: manager.id,
+            manager: manager.clone(),
+            profile: Profile::default(),
+            leagues,
+            list_state,
+            open: None,
+            pages: HashMap::new(),
+            requested: HashSet::new(),
+            table_state: TableState::default(),
+            h2h: H2H::new(snapshot),
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) -> Option<Action> {
+        if self.h2h.is_open() {
+            self.h2h.handle_action(action);
+            return None;
+        }
+        let Some((league_id, page)) = self.open else {
+            return match action {
+                Action::Escape => Some(Action::SwitchMode(Mode::Home)),
+                Action::Up => {
+                    self.list_state.select_previous();
+                    None
+                },
+                Action::Down => {
+                    self.list_state.select_next();
+                    None
+                },
+                Action::Enter => {
+                    let league = self.list_state.selected().and_then(|i| self.leagues.get(i));
+                    match league.map(|l| (l.kind, l.id)) {
+                        Some((Kind::Classic, id)) => self.open_page(id, 1),
+                        Some((Kind::H2H, id)) => {
+                            self.h2h.open(id);
+                            None
+                        },
+                        Some((Kind::Rival, id)) => Some(Action::ViewManager(id.to_string())),
+                        None => None,
+                    }
+                },
+                _ => None,
+            };
+        };
+        match action {
+            Action::Escape => {
+                self.open = None;
+                None
+            },
+            Action::Up => {
+                self.table_state.select_previous();
+                None
+            },
+            Action::Down => {
+                self.table_state.select_next();
+                None
+            },
+            Action::Left => self.step_page(-1),
+            Action::Right => self.step_page(1),
+            Action::Enter => {
+                let entry = self
+                    .pages
+                    .get(&(league_id, page))
+                    .and_then(|s| self.table_state.selected().and_then(|i| s.standings.results.get(i)));
+                entry.map(|e| Action::ViewManager(e.entry.to_string()))
+            },
+            _ => None,
+        }
+    }
+
+    /// List the own manager's leagues, or nothing while a newly switched profile's team is loading.
+    fn set_leagues(&mut self) {
+        self.leagues = match self.manager.id == self.own_manager_id {
+            true => league_rows(&self.manager, &self.profile),
+            false => Vec::new(),
+        };
+        self.list_state.select((!self.leagues.is_empty()).then_some(0));
+    }
+
+    /// A manager's team and name from any standings loaded, e.g. `Team (Manager)`.
+    fn entry_name(&self, entry: i64) -> Option<String> {
+        let e = self.pages.values().flat_map(|s| s.standings.results.iter()).find(|e| e.entry == entry)?;
+        Some(format!("{} ({})", e.entry_name, e.player_name))
+    }
+
+    /// Show a page of standings, asking for it to be loaded if we don't have it yet.
+    fn open_page(&mut self, league_id: i64, page: i64) -> Option<Action> {
+        self.open = Some((league_id, page));
+        self.table_state.select(Some(0));
+        if self.pages.contains_key(&(league_id, page)) || !self.requested.insert((league_id, page)) {
+            return None;
+        }
+        Some(Action::LoadClassicStandings(league_id, page))
+    }
+
+    fn step_page(&mut self, delta: i64) -> Option<Action> {
+        let (league_id, page) = self.open?;
+        let has_next = self.pages.get(&(league_id, page)).is_some_and(|s| s.standings.has_next);
+        if (delta < 0 && page <= 1) || (delta > 0 && !has_next) {
+            return None;
+        }
+        self.open_page(league_id, page + delta)
+    }
+
+    fn draw_list(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let items: Vec<ListItem> = self
+            .leagues
+            .iter()
+            .map(|l| {
+                let (kind, name) = match l.kind {
+                    Kind::Classic => ("Classic", l.name.clone()),
+                    Kind::H2H => ("H2H", l.name.clone()),
+                    Kind::Rival => ("Rival", self.entry_name(l.id).unwrap_or_else(|| l.name.clone())),
+                };
+                let rank = match l.entry_rank {
+                    0 => String::new(),
+                    rank => rank.separate_with_commas(),
+                };
+                let mut spans = vec![
+                    Span::raw(if l.favourite { "★ " } else { "  " }).yellow(),
+                    Span::raw(format!("{:<8}", kind)).dim(),
+                    Span::raw(format!("{:<40}", name)),
+                ];
+                if l.kind != Kind::Rival {
+                    spans.push(Span::raw(format!("{:>10} ", rank)));
+                    spans.push(movement(l.entry_rank, l.entry_last_rank));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::default().borders(Borders::ALL).title(" Leagues "));
+        f.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn draw_standings(&mut self, f: &mut Frame<'_>, area: Rect, league_id: i64, page: i64) {
+        let Some(standings) = self.pages.get(&(league_id, page)) else {
+            let p = Paragraph::new("Loading standings…")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL).title(" Standings "));
+            f.render_widget(p, area);
+            return;
+        };
+        let header = Row::new(["Rank", "", "Team", "Manager", "GW", "Total"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = standings.standings.results.iter().map(|e| {
+            let row = Row::new(vec![
+                Cell::from(e.rank.separate_with_commas()),
+                Cell::from(movement(e.rank, e.last_rank)),
+                Cell::from(e.entry_name.clone()),
+                Cell::from(e.player_name.clone()),
+                Cell::from(e.event_total.to_string()),
+                Cell::from(e.total.separate_with_commas()),
+            ]);
+            if e.entry == self.own_manager_id {
+                row.bold()
+            } else if self.profile.rivals.contains(&e.entry) {
+                row.yellow()
+            } else {
+                row
+            }
+        });
+        let title = format!(" {} · page {} ", standings.league.name, page);
+        let table = Table::new(rows, [
+            Constraint::Length(8),
+            Constraint::Length(1),
+            Constraint::Min(20),
+            Constraint::Min(20),
+            Constraint::Length(4),
+            Constraint::Length(7),
+        ])
+        .header(header)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(
+            Block::default().borders(Borders::ALL).title(title).title_bottom(
+                Line::from(self.config.keybindings.hints(Mode::Leagues, &[
+                    (&[Action::Left, Action::Right], "page"),
+                    (&[Action::Enter], "view team"),
+                    (&[Action::Escape], "back"),
+                ]))
+                .dim()
+                .right_aligned(),
+            ),
+        );
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+}
+
+impl Component for Leagues {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.h2h.register_action_handler(tx.clone());
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.h2h.register_keybindings(config.keybindings.clone());
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::ClassicStandings(page, standings)) => {
+                let key = (standings.league.id, page);
+                self.requested.remove(&key);
+                self.pages.insert(key, *standings);
+                None
+            },
+            Some(Event::H2HStandings(page, standings)) => {
+                self.h2h.set_standings(page, *standings);
+                None
+            },
+            Some(Event::H2HMatches(league_id, gameweek, matches)) => {
+                self.h2h.set_matches(league_id, gameweek, *matches);
+                None
+            },
+            Some(Event::MatchupLoaded(gameweek, entries, teams, live)) => {
+                self.h2h.set_matchup(gameweek, entries, *teams, live);
+                None
+            },
+            Some(Event::Refreshed(snapshot)) => {
+                if snapshot.manager.id == self.own_manager_id {
+                    self.manager = snapshot.manager.clone();
+                    self.set_leagues();
+                    self.h2h.load(&snapshot);
+                }
+                None
+            },
+            Some(Event::ProfileSwitched(_, profile)) => {
+                self.own_manager_id = profile.manager_id;
+                self.profile = profile;
+                self.open = None;
+                self.set_leagues();
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SwitchMode(mode) => self.mode = mode,
+            Action::Refresh => {
+                self.pages.clear();
+                self.h2h.clear();
+                if let Some((league_id, page)) = self.open {
+                    return Ok(self.open_page(league_id, page));
+                }
+            },
+            Action::RequestFailed(request, _) => {
+                match *request {
+                    Action::LoadClassicStandings(league_id, page) => {
+                        self.requested.remove(&(league_id, page));
+                    },
+                    request => self.h2h.request_failed(&request),
+                }
+            },
+            action if self.mode == Mode::Leagues => return Ok(self.handle_action(action)),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Leagues {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        match self.open {
+            Some((league_id, page)) => self.draw_standings(f, area, league_id, page),
+            None if self.h2h.is_open() => self.h2h.draw(f, area),
+            None => self.draw_list(f, area),
+        }
+        Ok(())
+    }
+}
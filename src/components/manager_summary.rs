@@ -1,4 +1,5 @@
 use color_eyre::eyre::Result;
+use fpl_api::manager::{EntryHistory, GWTeam};
 use ratatui::{prelude::*, widgets::*};
 use serde::{Deserialize, Serialize};
 use serde_json::error::Category;
@@ -13,11 +14,91 @@ use crate::{
 
 pub struct ManagerSummary {
     details: fpl_api::manager::Manager,
+    gameweek: Option<GameweekSummary>,
+    live_points: Option<i64>,
+    /// Projected points of the what-if lineup, and how that compares with the real one.
+    what_if: Option<(f64, f64)>,
+}
+
+/// The gameweek on the pitch, when it isn't necessarily the manager's current one.
+struct GameweekSummary {
+    is_current: bool,
+    history: EntryHistory,
+    chip: Option<String>,
+    captain: Option<String>,
+    auto_subs: Vec<String>,
 }
 
 impl ManagerSummary {
     pub fn new(details: fpl_api::manager::Manager) -> Self {
-        Self { details }
+        Self { details, gameweek: None, live_points: None, what_if: None }
+    }
+
+    pub fn set_gameweek(&mut self, is_current: bool, gw_team: &GWTeam) {
+        self.gameweek = Some(GameweekSummary {
+            is_current,
+            history: gw_team.entry_history.clone(),
+            chip: gw_team.active_chip.clone(),
+            captain: None,
+            auto_subs: Vec::new(),
+        });
+    }
+
+    pub fn set_captain(&mut self, captain: Option<String>) {
+        if let Some(gw) = self.gameweek.as_mut() {
+            gw.captain = captain;
+        }
+    }
+
+    pub fn set_auto_subs(&mut self, auto_subs: Vec<String>) {
+        if let Some(gw) = self.gameweek.as_mut() {
+            gw.auto_subs = auto_subs;
+        }
+    }
+
+    pub fn set_what_if(&mut self, what_if: Option<(f64, f64)>) {
+        self.what_if = what_if;
+    }
+
+    pub fn set_live_points(&mut self, points: i64) {
+        self.live_points = Some(points);
+    }
+
+    fn is_current(&self) -> bool {
+        self.gameweek.as_ref().is_none_or(|gw| gw.is_current)
+    }
+
+    fn gw_rank_line(&mut self) -> String {
+        let rank = match &self.gameweek {
+            Some(gw) if !gw.is_current => gw.history.rank.unwrap_or(0),
+            _ => *self.details.summary_event_rank.get_or_insert(0),
+        };
+        format!("GW Rank: {}", rank.separate_with_commas())
+    }
+
+    fn gw_points_line(&self) -> String {
+        match (self.live_points, &self.gameweek) {
+            (Some(points), _) if self.is_current() => format!("GW Points: {} (live)", points),
+            (_, Some(gw)) if !gw.is_current => format!("GW Points: {}", gw.history.points),
+            _ => format!("GW Points: {}", self.details.summary_event_points),
+        }
+    }
+
+    fn gw_detail_lines(&self) -> Vec<Line<'_>> {
+        let Some(gw) = &self.gameweek else {
+            return Vec::new();
+        };
+        let mut lines = vec![Line::from(format!("Captain: {}", gw.captain.as_deref().unwrap_or("-")))];
+        for sub in &gw.auto_subs {
+            lines.push(Line::from(format!("Auto-sub: {}", sub)));
+        }
+        if let Some(chip) = &gw.chip {
+            lines.push(Line::from(format!("Chip: {}", chip)));
+        }
+        if gw.history.event_transfers_cost > 0 {
+            lines.push(Line::from(format!("Transfer hits: -{}", gw.history.event_transfers_cost)));
+        }
+        lines
     }
 
     fn get_player_flag_emoji(&self) -> &str {
@@ -26,26 +107,32 @@ impl ManagerSummary {
     }
 
     pub fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
-        let p = Paragraph::new(vec![
+        let mut lines = vec![
             Line::styled(self.details.name.clone(), Style::default().bg(Color::Indexed(127_u8)).fg(Color::White)),
             Line::from(format!(
                 "({} {}, {})",
-                &self.details.player_first_name,
-                &self.details.player_last_name,
+                self.details.player_first_name,
+                self.details.player_last_name,
                 self.get_player_flag_emoji()
             )),
             Line::from("-------------------------"),
             Line::from(format!("Overall Rank: {}", self.details.summary_overall_rank.separate_with_commas())),
             Line::from(format!("Overall Points: {}", self.details.summary_overall_points)),
             Line::from("-------------------------"),
-            Line::from(format!("GW Rank: {}", self.details.summary_event_rank.get_or_insert(0).separate_with_commas())),
-            Line::styled(
-                format!("GW Points: {}", self.details.summary_event_points),
-                Style::default().bg(Color::Indexed(125_u8)),
-            ),
-        ])
-        .block(Block::default().borders(Borders::ALL).padding(Padding::new(0, 0, 5, 5)))
-        .alignment(Alignment::Center);
+            Line::from(self.gw_rank_line()),
+            Line::styled(self.gw_points_line(), Style::default().bg(Color::Indexed(125_u8))),
+        ];
+        lines.extend(self.gw_detail_lines());
+        if let Some((projected, delta)) = self.what_if {
+            lines.push(Line::from("-------------------------"));
+            lines.push(Line::styled(
+                format!("What-if: {:.1} pts ({:+.1})", projected, delta),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        let p = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).padding(Padding::new(0, 0, 5, 5)))
+            .alignment(Alignment::Center);
         f.render_widget(p, area);
         Ok(())
     }
@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use fpl_api::bootstrap::Element;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    config::Config,
+    data_source::Snapshot,
+    event::Event,
+    lineup,
+    mode::Mode,
+    planner::{self, Draft, Drafts, Outcome, Transfer},
+    projection::Projection,
+};
+
+const POSITIONS: [&str; 5] = ["", "GK", "DEF", "MID", "FWD"];
+/// Gameweeks a new plan covers; `]` past the last one adds another.
+const SPAN: i64 = 5;
+
+fn money(tenths: i64) -> String {
+    format!("£{:.1}m", tenths as f64 / 10.0)
+}
+
+/// A sandbox for planning transfers over the coming gameweeks, starting from the manager's own picks.
+pub struct Planner {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    elements: HashMap<i64, Element>,
+    team_names: HashMap<i64, String>,
+    projection: Projection,
+    last_event: i64,
+    own_manager_id: i64,
+    /// Whether the team on show is the manager's own, so a history that arrives is theirs.
+    viewing_own: bool,
+    history_requested: bool,
+
+    /// An empty plan from the current picks, for starting over.
+    base: Draft,
+    draft: Draft,
+    /// Index into the draft's gameweeks.
+    gameweek: usize,
+    table_state: TableState,
+    /// The player being sold while a replacement is picked in the browser.
+    selling: Option<i64>,
+    drafts: Drafts,
+    /// Saved drafts, while the list of them is open.
+    saved: Option<(Vec<Draft>, ListState)>,
+    /// The name being typed for saving.
+    naming: Option<String>,
+    last_error: Option<String>,
+}
+
+impl Planner {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let mut planner = Self {
+            command_tx: None,
+            config: Config::default(),
+            mode: Mode::Home,
+            elements: HashMap::new(),
+            team_names: HashMap::new(),
+            projection: Projection::default(),
+            last_event: 0,
+            own_manager_id: snapshot.manager.id,
+            viewing_own: true,
+            history_requested: false,
+            base: Draft::default(),
+            draft: Draft::default(),
+            gameweek: 0,
+            table_state: TableState::default().with_selected(Some(0)),
+            selling: None,
+            drafts: Drafts::default(),
+            saved: None,
+            naming: None,
+            last_error: None,
+        };
+        planner.load(snapshot);
+        planner.draft = planner.base.clone();
+        planner
+    }
+
+    fn load(&mut self, snapshot: &Snapshot) {
+        self.elements = snapshot.bootstrap.elements.iter().map(|e| (e.id, e.clone())).collect();
+        self.team_names = snapshot.bootstrap.teams.iter().map(|t| (t.id, t.short_name.clone())).collect();
+        self.last_event = snapshot.bootstrap.events.iter().map(|e| e.id).max().unwrap_or(0);
+        self.projection =
+            Projection::new(&snapshot.bootstrap, &snapshot.fixtures, self.config.config.projection_gameweeks);
+        let squad = snapshot
+            .gw_picks
+            .picks
+            .iter()
+            .filter(|p| lineup::is_starter(p) || lineup::is_bench(p))
+            .map(|p| p.element)
+            .collect();
+        let free_transfers = match self.base.base_event == snapshot.gw_picks.entry_history.event {
+            true => self.base.free_transfers,
+            false => 1,
+        };
+        self.base = Draft::new(
+            squad,
+            snapshot.gw_picks.entry_history.bank,
+            free_transfers,
+            snapshot.gw_picks.entry_history.event,
+            SPAN,
+            self.last_event,
+        );
+    }
+
+    fn element(&self, id: i64) -> Option<&Element> {
+        self.elements.get(&id)
+    }
+
+    fn outcome(&self) -> Result<Outcome, String> {
+        let mut outcomes = self.draft.outcomes(&self.elements)?;
+        match self.gameweek < outcomes.len() {
+            true => Ok(outcomes.swap_remove(self.gameweek)),
+            false => Err("No gameweeks left to plan this season".to_string()),
+        }
+    }
+
+    /// The selected gameweek's squad, by position and then price.
+    fn squad(&self, outcome: &Outcome) -> Vec<i64> {
+        let mut squad = outcome.squad.clone();
+        squad.sort_by_key(|id| self.element(*id).map(|e| (e.element_type, -e.now_cost)));
+        squad
+    }
+
+    fn selected_player(&self) -> Option<i64> {
+        let outcome = self.outcome().ok()?;
+        self.table_state.selected().and_then(|i| self.squad(&outcome).get(i).copied())
+    }
+
+    fn request_history(&mut self) -> Option<Action> {
+        if self.history_requested || !self.viewing_own {
+            return None;
+        }
+        self.history_requested = true;
+        Some(Action::LoadHistory)
+    }
+
+    /// Send the browser off to find a replacement for the selected player.
+    fn sell(&mut self) -> Option<Action> {
+        let outcome = match self.outcome() {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                self.last_error = Some(e);
+                return None;
+            },
+        };
+        let element = self.selected_player().and_then(|id| self.element(id))?;
+        let (id, element_type, budget) = (element.id, element.element_type, outcome.bank + element.now_cost);
+        self.selling = Some(id);
+        if let Some(tx) = &self.command_tx {
+            let _ = tx.send(Action::SwitchMode(Mode::Browser));
+        }
+        Some(Action::ChoosePlayer(element_type, budget))
+    }
+
+    fn buy(&mut self, element_in: i64) {
+        let Some(element_out) = self.selling.take() else {
+            return;
+        };
+        let transfer = Transfer { element_out, element_in };
+        self.last_error = self.draft.add_transfer(self.gameweek, transfer, &self.elements).err();
+    }
+
+    fn undo(&mut self) {
+        self.last_error = self.draft.undo_transfer(self.gameweek, &self.elements).err();
+    }
+
+    fn next_gameweek(&mut self) {
+        if self.gameweek + 1 < self.draft.gameweeks.len() {
+            self.gameweek += 1;
+            return;
+        }
+        let next = self.draft.gameweeks.last().map_or(self.draft.base_event, |gw| gw.event) + 1;
+        if next <= self.last_event {
+            self.draft.gameweeks.push(planner::PlannedGameweek { event: next, transfers: Vec::new() });
+            self.gameweek = self.draft.gameweeks.len() - 1;
+        }
+    }
+
+    fn open_saved(&mut self) {
+        let saved = self.drafts.list();
+        if saved.is_empty() {
+            self.last_error = Some("No saved drafts yet".to_string());
+            return;
+        }
+        let selected = saved.iter().position(|d| d.name == self.draft.name).unwrap_or(0);
+        self.saved = Some((saved, ListState::default().with_selected(Some(selected))));
+    }
+
+    fn handle_saved_action(&mut self, action: Action) -> Option<Action> {
+        let (saved, state) = self.saved.as_mut()?;
+        match action {
+            Action::Escape => self.saved = None,
+            Action::Up => state.select_previous(),
+            Action::Down => state.select_next(),
+            Action::Enter => {
+                if let Some(draft) = state.selected().and_then(|i| saved.get(i)) {
+                    self.draft = draft.clone();
+                    self.gameweek = 0;
+                    self.last_error = self.draft.outcomes(&self.elements).err();
+                }
+                self.saved = None;
+            },
+            Action::DeleteDraft => {
+                if let Some(draft) = state.selected().and_then(|i| saved.get(i)) {
+                    if let Err(e) = self.drafts.delete(&draft.name) {
+                        return Some(Action::Error(format!("Unable to delete draft {}: {}", draft.name, e)));
+                    }
+                }
+                self.saved = None;
+                self.open_saved();
+            },
+            _ => {},
+        }
+        None
+    }
+
+    fn handle_action(&mut self, action: Action) -> Option<Action> {
+        if self.saved.is_some() {
+            return self.handle_saved_action(action);
+        }
+        match action {
+            Action::Escape => return Some(Action::SwitchMode(Mode::Home)),
+            Action::Up => self.table_state.select_previous(),
+            Action::Down => self.table_state.select_next(),
+            Action::PreviousGameweek => self.gameweek = self.gameweek.saturating_sub(1),
+            Action::NextGameweek => self.next_gameweek(),
+            Action::Sell | Action::Enter => return self.sell(),
+            Action::Undo => self.undo(),
+            Action::ResetDraft => {
+                self.draft = self.base.clone();
+                self.gameweek = 0;
+                self.last_error = None;
+            },
+            Action::SaveDraft => return self.set_naming(true),
+            Action::OpenDrafts => self.open_saved(),
+            _ => {},
+        }
+        None
+    }
+
+    fn set_naming(&mut self, naming: bool) -> Option<Action> {
+        self.naming = naming.then(|| self.draft.name.clone());
+        Some(Action::CaptureKeys(naming))
+    }
+
+    fn handle_naming_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let name = self.naming.as_mut()?;
+        match key.code {
+            KeyCode::Esc => self.set_naming(false),
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return None;
+                }
+                self.draft.name = name;
+                if let Err(e) = self.drafts.save(&self.draft) {
+                    self.last_error = Some(format!("Unable to save draft: {}", e));
+                }
+                self.set_naming(false)
+            },
+            KeyCode::Backspace => {
+                name.pop();
+                None
+            },
+            KeyCode::Char(c) => {
+                name.push(c);
+                None
+            },
+            _ => None,
+        }
+    }
+
+    fn draw_header(&self, f: &mut Frame<'_>, area: Rect) {
+        let title = match (&self.naming, self.draft.name.is_empty()) {
+            (Some(_), _) => " Save draft as ".to_string(),
+            (None, true) => " Unsaved draft ".to_string(),
+            (None, false) => format!(" Draft: {} ", self.draft.name),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if let Some(name) = &self.naming {
+            let line = Line::from(vec![Span::raw(name.clone()), Span::raw("▏").slow_blink()]);
+            f.render_widget(Paragraph::new(line).block(block), area);
+            return;
+        }
+        let titles = self.draft.gameweeks.iter().map(|gw| {
+            match gw.transfers.len() {
+                0 => format!("GW{}", gw.event),
+                n => format!("GW{} ({})", gw.event, n),
+            }
+        });
+        let tabs = Tabs::new(titles)
+            .select(self.gameweek)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(block);
+        f.render_widget(tabs, area);
+    }
+
+    fn draw_squad(&mut self, f: &mut Frame<'_>, area: Rect, outcome: &Outcome) {
+        let header = Row::new(["Pos", "Name", "Team", "£", "xP"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let squad = self.squad(outcome);
+        let rows = squad.iter().filter_map(|id| self.element(*id)).map(|e| {
+            let style = match self.draft.squad.contains(&e.id) {
+                true => Style::default(),
+                false => Style::default().fg(Color::Green),
+            };
+            Row::new([
+                Cell::from(POSITIONS.get(e.element_type as usize).copied().unwrap_or("")),
+                Cell::from(e.web_name.clone()),
+                Cell::from(self.team_names.get(&e.team).cloned().unwrap_or_default()),
+                Cell::from(Line::from(format!("{:.1}", e.now_cost as f64 / 10.0)).right_aligned()),
+                Cell::from(Line::from(format!("{:.1}", self.projection.gameweek(e.id, outcome.event))).right_aligned()),
+            ])
+            .style(style)
+        });
+        let widths = [
+            Constraint::Length(4),
+            Constraint::Min(16),
+            Constraint::Length(4),
+            Constraint::Length(5),
+            Constraint::Length(5),
+        ];
+        let title = format!(" Squad for GW{} ", outcome.event);
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_transfers(&self, f: &mut Frame<'_>, area: Rect, outcome: &Outcome) {
+        let name = |id: i64| self.element(id).map_or_else(|| id.to_string(), |e| e.web_name.clone());
+        let cost = |id: i64| self.element(id).map_or(0, |e| e.now_cost);
+        let xp = |id: i64| self.projection.expected(id);
+        let transfers = self.draft.gameweeks.get(self.gameweek).map_or(&[][..], |gw| gw.transfers.as_slice());
+        let mut lines: Vec<Line> = transfers
+            .iter()
+            .map(|t| {
+                let gain = xp(t.element_in) - xp(t.element_out);
+                Line::from(vec![
+                    Span::styled(format!("{} ({})", name(t.element_out), money(cost(t.element_out))), Color::Red),
+                    Span::raw(" → "),
+                    Span::styled(format!("{} ({})", name(t.element_in), money(cost(t.element_in))), Color::Green),
+                    Span::raw(format!("  {:+.1} xP", gain)).dim(),
+                ])
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("No transfers planned").dim());
+        }
+        lines.push(Line::default());
+        let hit = match outcome.hit {
+            0 => Span::raw("no hit"),
+            hit => Span::styled(format!("-{} pts", hit), Color::Red),
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("Free transfers: {}   Made: {}   ", outcome.free_transfers, transfers.len())),
+            hit,
+        ]));
+        lines.push(Line::from(format!("Bank: {}   Squad value: {}", money(outcome.bank), money(outcome.value))));
+        f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Transfers ")), area);
+    }
+
+    fn draw_summary(&self, f: &mut Frame<'_>, area: Rect, outcomes: &[Outcome]) {
+        let header = Row::new(["GW", "FT", "Made", "Hit", "Bank", "Value"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let rows = outcomes.iter().zip(&self.draft.gameweeks).enumerate().map(|(i, (o, gw))| {
+            let row = Row::new([
+                o.event.to_string(),
+                o.free_transfers.to_string(),
+                gw.transfers.len().to_string(),
+                if o.hit > 0 { format!("-{}", o.hit) } else { String::new() },
+                money(o.bank),
+                money(o.value),
+            ]);
+            if i == self.gameweek {
+                row.add_modifier(Modifier::BOLD)
+            } else {
+                row
+            }
+        });
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(7),
+            Constraint::Length(8),
+        ];
+        let total: i64 = outcomes.iter().map(|o| o.hit).sum();
+        let title = format!(" Plan · hits -{} pts ", total);
+        let table = Table::new(rows, widths).header(header).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
+    }
+
+    fn draw_saved(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let Some((saved, state)) = self.saved.as_mut() else {
+            return;
+        };
+        let area = Layout::default().constraints([Constraint::Percentage(100)]).margin(6).split(area)[0];
+        let items = saved.iter().map(|d| {
+            let transfers: usize = d.gameweeks.iter().map(|gw| gw.transfers.len()).sum();
+            ListItem::new(format!("{}  (from GW{}, {} transfers)", d.name, d.base_event, transfers))
+        });
+        let list = List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)).block(
+            Block::default().borders(Borders::ALL).title(" Saved drafts ").title_bottom(
+                Line::from(self.config.keybindings.hints(Mode::Planner, &[
+                    (&[Action::Enter], "load"),
+                    (&[Action::DeleteDraft], "delete"),
+                    (&[Action::Escape], "close"),
+                ]))
+                .dim()
+                .right_aligned(),
+            ),
+        );
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, state);
+    }
+}
+
+impl Component for Planner {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.projection.set_horizon(config.config.projection_gameweeks);
+        self.drafts = Drafts::new(config.config._data_dir.join("drafts"));
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Key(key_event)) if self.mode == Mode::Planner && self.naming.is_some() => {
+                self.handle_naming_key(key_event)
+            },
+            Some(Event::ManagerHistory(manager_id, history)) if manager_id == self.own_manager_id => {
+                self.history_requested = false;
+                let event = history.current.last().map(|gw| gw.event);
+                let free = planner::free_transfers(&history);
+                for draft in [&mut self.base, &mut self.draft] {
+                    if Some(draft.base_event) == event {
+                        draft.free_transfers = free;
+                    }
+                }
+                None
+            },
+            Some(Event::Refreshed(snapshot)) => {
+                self.viewing_own = snapshot.manager.id == self.own_manager_id;
+                if self.viewing_own {
+                    self.load(&snapshot);
+                    if self.draft.name.is_empty() && !self.draft.has_transfers() {
+                        self.draft = self.base.clone();
+                        self.gameweek = 0;
+                    }
+                }
+                None
+            },
+            Some(Event::ProfileSwitched(_, profile)) if profile.manager_id != self.own_manager_id => {
+                // Drafts belong to the team they were made for
+                self.own_manager_id = profile.manager_id;
+                self.base = Draft::default();
+                self.draft = Draft::default();
+                self.gameweek = 0;
+                self.history_requested = false;
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SwitchMode(mode) => {
+                self.mode = mode;
+                if mode == Mode::Planner {
+                    return Ok(self.request_history());
+                }
+            },
+            Action::PlayerChosen(element) => self.buy(element),
+            action if self.mode == Mode::Planner => return Ok(self.handle_action(action)),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Planner {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(10), Constraint::Length(1)])
+            .split(area);
+        self.draw_header(f, layout[0]);
+
+        match self.draft.outcomes(&self.elements) {
+            Ok(outcomes) if self.gameweek < outcomes.len() => {
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(layout[1]);
+                let right = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(8), Constraint::Length(outcomes.len() as u16 + 3)])
+                    .split(columns[1]);
+                let outcome = &outcomes[self.gameweek];
+                self.draw_squad(f, columns[0], outcome);
+                self.draw_transfers(f, right[0], outcome);
+                self.draw_summary(f, right[1], &outcomes);
+            },
+            Ok(_) => {
+                let message = Paragraph::new("No gameweeks left to plan this season").dim();
+                f.render_widget(message.block(Block::default().borders(Borders::ALL)), layout[1]);
+            },
+            Err(e) => {
+                let message = Paragraph::new(format!("This draft no longer holds: {}", e)).red();
+                f.render_widget(message.block(Block::default().borders(Borders::ALL)), layout[1]);
+            },
+        }
+
+        let footer = match &self.last_error {
+            Some(e) => Line::styled(format!(" {}", e), Style::default().fg(Color::Red)),
+            None => {
+                Line::from(self.config.keybindings.hints(Mode::Planner, &[
+                    (&[Action::Sell], "sell"),
+                    (&[Action::Undo], "undo"),
+                    (&[Action::PreviousGameweek, Action::NextGameweek], "gameweek"),
+                    (&[Action::SaveDraft], "save"),
+                    (&[Action::OpenDrafts], "load"),
+                    (&[Action::ResetDraft], "start over"),
+                    (&[Action::Escape], "back"),
+                ]))
+                .dim()
+            },
+        };
+        f.render_widget(footer, layout[2]);
+        self.draw_saved(f, area);
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
 use color_eyre::eyre::Result;
-use fpl_api::bootstrap::Element;
+use fpl_api::{
+    bootstrap::Element,
+    manager::Pick,
+    players::{Explain, Stats},
+};
 use image::DynamicImage;
 use ratatui::{prelude::*, widgets::*};
 use ratatui_image::{
@@ -15,8 +19,17 @@ use super::{Component, Frame};
 use crate::{
     action::Action,
     config::{Config, KeyBindings},
+    lineup, price_history,
+    price_predictor::Prediction,
 };
 
+/// Which way a player went in an automatic substitution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Substitution {
+    On,
+    Off,
+}
+
 pub struct PlayerCard {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
@@ -24,10 +37,25 @@ pub struct PlayerCard {
     team: String,
     pub details: Element,
     is_active: bool,
+    /// Marked as the first of two players to swap.
+    is_marked: bool,
     image_picker: Option<Picker>,
     image_state: Option<StatefulProtocol>,
     team_image_state: Option<StatefulProtocol>,
     pub position: i64,
+    pick: Pick,
+    /// The multiplier the player actually scores with, after any change of armband.
+    multiplier: i64,
+    substitution: Option<Substitution>,
+    live: Option<Stats>,
+    /// How the gameweek's points were scored, fixture by fixture.
+    explain: Vec<Explain>,
+    /// Projected points for the coming gameweeks, as `(event, points)`.
+    projection: Vec<(i64, f64)>,
+    /// Change in price since the last run, when there's a record of it.
+    price_change: Option<i64>,
+    /// How close the price is to moving, for players in the manager's squad.
+    price_prediction: Option<Prediction>,
     debug: Vec<u8>,
 }
 
@@ -38,7 +66,7 @@ impl PlayerCard {
         details: Element,
         image_picker: Option<Picker>,
         team_image_state: Option<StatefulProtocol>,
-        position: i64,
+        pick: Pick,
     ) -> Self {
         PlayerCard {
             command_tx: None,
@@ -47,10 +75,19 @@ impl PlayerCard {
             team,
             details,
             is_active: false,
+            is_marked: false,
             image_picker,
             image_state: None,
             team_image_state,
-            position,
+            position: pick.position,
+            multiplier: pick.multiplier,
+            pick,
+            substitution: None,
+            live: None,
+            explain: Vec::new(),
+            projection: Vec::new(),
+            price_change: None,
+            price_prediction: None,
             debug: Vec::new(),
         }
     }
@@ -59,6 +96,10 @@ impl PlayerCard {
         self.is_active = state;
     }
 
+    pub fn mark_swap(&mut self, state: bool) {
+        self.is_marked = state;
+    }
+
     pub fn set_image(&mut self, image: DynamicImage) {
         if let Some(pc) = self.image_picker.as_mut() {
             let protocol = pc.new_resize_protocol(image);
@@ -66,6 +107,127 @@ impl PlayerCard {
         }
     }
 
+    pub fn set_live(&mut self, live: Option<Stats>) {
+        self.live = live;
+    }
+
+    pub fn set_explain(&mut self, explain: Vec<Explain>) {
+        self.explain = explain;
+    }
+
+    pub fn set_projection(&mut self, projection: Vec<(i64, f64)>) {
+        self.projection = projection;
+    }
+
+    pub fn set_price_change(&mut self, price_change: Option<i64>) {
+        self.price_change = price_change;
+    }
+
+    pub fn set_price_prediction(&mut self, price_prediction: Option<Prediction>) {
+        self.price_prediction = price_prediction;
+    }
+
+    /// An arrow when the price looks close to rising or falling.
+    fn price_badge(&self) -> Option<Span<'static>> {
+        let (arrow, colour) = self.price_prediction?.indicator()?;
+        Some(Span::styled(format!(" £{} ", arrow), Style::default().fg(colour).bold()))
+    }
+
+    /// The price, with how it has moved since the last run and since the gameweek started.
+    fn price_line(&self) -> Line<'static> {
+        let mut spans = vec![Span::raw(format!("Price: £{:.1}m", self.details.now_cost as f64 / 10.0))];
+        let changes = [(self.price_change.unwrap_or(0), "since last run"), (self.details.cost_change_event, "this GW")];
+        for (change, label) in changes.into_iter().filter(|(change, _)| *change != 0) {
+            let colour = if change > 0 { Color::Green } else { Color::Red };
+            spans.push(Span::styled(format!("  {} {}", price_history::format_change(change), label), colour));
+        }
+        if let Some(prediction) = self.price_prediction {
+            if let Some((arrow, colour)) = prediction.indicator() {
+                let text = format!("  {} {:.0}% to a change", arrow, prediction.progress.abs() * 100.0);
+                spans.push(Span::styled(text, colour));
+            }
+        }
+        Line::from(spans)
+    }
+
+    /// Projected points for the next gameweek and the whole window, or FPL's own numbers without a projection.
+    fn projection_lines(&self) -> Vec<Line<'static>> {
+        let Some((event, next)) = self.projection.first() else {
+            return vec![
+                Line::from(format!("EP this: {}", self.details.ep_this)),
+                Line::from(format!("EP next : {}", self.details.ep_next)),
+            ];
+        };
+        let total: f64 = self.projection.iter().map(|(_, points)| points).sum();
+        vec![
+            Line::from(format!("xP GW{}: {:.1}", event, next)),
+            Line::from(format!("xP next {}: {:.1}", self.projection.len(), total)),
+        ]
+    }
+
+    /// One line per scoring stat, summed over the gameweek's fixtures.
+    fn breakdown_lines(&self) -> Vec<Line<'static>> {
+        let mut totals: Vec<(String, i64, i64)> = Vec::new();
+        for stat in self.explain.iter().flat_map(|e| e.stats.iter()) {
+            match totals.iter_mut().find(|(identifier, ..)| *identifier == stat.identifier) {
+                Some((_, value, points)) => {
+                    *value += stat.value;
+                    *points += stat.points;
+                },
+                None => totals.push((stat.identifier.clone(), stat.value, stat.points)),
+            }
+        }
+        totals
+            .into_iter()
+            .map(|(identifier, value, points)| {
+                // Assistant manager stats are prefixed, e.g. `mng_win`
+                let label = identifier.trim_start_matches("mng_").replace('_', " ");
+                Line::from(format!("{}: {} → {} pts", label, value, points))
+            })
+            .collect()
+    }
+
+    pub fn set_multiplier(&mut self, multiplier: i64) {
+        self.multiplier = multiplier;
+    }
+
+    pub fn set_substitution(&mut self, substitution: Option<Substitution>) {
+        self.substitution = substitution;
+    }
+
+    fn substitution_badge(&self) -> Option<Span<'static>> {
+        match self.substitution? {
+            Substitution::On => Some(Span::styled(" ▲ on ", Style::default().fg(Color::Green).bold())),
+            Substitution::Off => Some(Span::styled(" ▼ off ", Style::default().fg(Color::Red).bold())),
+        }
+    }
+
+    /// Gameweek points before the multiplier, from live stats when we have them.
+    pub fn points(&self) -> i64 {
+        self.live.as_ref().map_or(self.details.event_points, |l| l.total_points)
+    }
+
+    /// What the player adds to the gameweek total, showing the multiplier when there is one.
+    fn points_line(&self) -> String {
+        match self.multiplier {
+            0 | 1 => format!("Points: {}", self.points()),
+            m => format!("Points: {} ({}×{})", m * self.points(), self.points(), m),
+        }
+    }
+
+    /// C or V badge, marking a captain who lost the armband and a vice-captain who took it.
+    fn armband_badge(&self) -> Option<Span<'static>> {
+        let badge = |text: &'static str, bg: Color| Span::styled(text, Style::default().bg(bg).fg(Color::Black).bold());
+        match (self.pick.is_captain, self.pick.is_vice_captain) {
+            (true, _) if self.multiplier < self.pick.multiplier => Some(badge(" C ", Color::DarkGray).crossed_out()),
+            (true, _) if self.multiplier == 3 => Some(badge(" TC ", Color::Yellow)),
+            (true, _) => Some(badge(" C ", Color::Yellow)),
+            (_, true) if self.multiplier > 1 => Some(badge(" V→C ", Color::Yellow)),
+            (_, true) => Some(badge(" V ", Color::Gray)),
+            _ => None,
+        }
+    }
+
     pub fn has_image(&self) -> bool {
         // TODO: figure out why state is being shared
         // false
@@ -86,17 +248,35 @@ impl PlayerCard {
         f.render_widget(Clear, area);
         // TODO
         let block = Block::default().borders(Borders::ALL).border_set(symbols::border::DOUBLE);
-        let p = Paragraph::new(vec![
-            Line::styled(self.name.to_string(), Style::default().bg(Color::Indexed(127_u8)).fg(Color::White)),
-            Line::raw(self.team.clone()),
-            Line::from(format!("Points: {}", self.details.event_points)),
-            Line::from(format!("Total Goals: {}", self.details.goals_scored)),
-            Line::from(format!("Total Assists: {}", self.details.assists)),
-            Line::from(format!("EP this: {}", self.details.ep_this)),
-            Line::from(format!("EP next : {}", self.details.ep_next)),
-            Line::from(format!("Bonus: {}", self.details.bonus)),
-        ])
-        .alignment(Alignment::Center);
+        let mut title =
+            vec![Span::styled(self.name.to_string(), Style::default().bg(Color::Indexed(127_u8)).fg(Color::White))];
+        title.extend(self.armband_badge());
+        title.extend(self.substitution_badge());
+        let mut lines = vec![Line::from(title), Line::raw(self.team.clone()), Line::from(self.points_line())];
+        if self.details.element_type == lineup::ASSISTANT_MANAGER {
+            lines.push(Line::from("Assistant Manager").italic());
+            lines.push(Line::from(format!("Season points: {}", self.details.total_points)));
+        } else {
+            lines.extend([
+                Line::from(format!("Total Goals: {}", self.details.goals_scored)),
+                Line::from(format!("Total Assists: {}", self.details.assists)),
+            ]);
+            lines.push(self.price_line());
+            lines.extend(self.projection_lines());
+            lines.push(Line::from(format!("Bonus: {}", self.details.bonus)));
+        }
+        let breakdown = self.breakdown_lines();
+        if !breakdown.is_empty() {
+            lines.push(Line::from("-------------------------"));
+            lines.extend(breakdown);
+        }
+        if let Some(live) = self.live.as_ref().filter(|_| self.details.element_type != lineup::ASSISTANT_MANAGER) {
+            lines.push(Line::from("-------------------------"));
+            lines.push(Line::from(format!("Minutes: {}", live.minutes)));
+            lines.push(Line::from(format!("Goals: {}  Assists: {}", live.goals_scored, live.assists)));
+            lines.push(Line::from(format!("Bonus: {}  BPS: {}", live.bonus, live.bps)));
+        }
+        let p = Paragraph::new(lines).alignment(Alignment::Center);
 
         f.render_widget(p, layouts[2]);
         f.render_widget(block, area);
@@ -124,7 +304,6 @@ impl Component for PlayerCard {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        action == Action::Tick;
         Ok(None)
     }
 
@@ -137,9 +316,10 @@ impl Component for PlayerCard {
             .constraints([Constraint::Fill(1), Constraint::Length(6), Constraint::Fill(1)])
             .direction(Direction::Horizontal)
             .split(layouts[1])[1];
-        let color_idx = match self.is_active {
-            true => 127u8,
-            false => 255u8,
+        let color_idx = match (self.is_marked, self.is_active) {
+            (true, _) => 11u8,
+            (_, true) => 127u8,
+            (_, false) => 255u8,
         };
         let border_type = match self.is_active {
             true => BorderType::Thick,
@@ -157,11 +337,14 @@ impl Component for PlayerCard {
             "d" => name_details.push(Span::from("⚠️")),
             _ => {},
         };
+        name_details.extend(self.armband_badge());
+        name_details.extend(self.substitution_badge());
+        name_details.extend(self.price_badge());
 
         let p = Paragraph::new(vec![
             Line::from(name_details),
             // Line::raw(self.team.clone()),
-            Line::from(format!("Points: {}", self.details.event_points)),
+            Line::from(self.points_line()),
         ])
         .alignment(Alignment::Center)
         .block(b);
@@ -79,7 +79,6 @@ impl Component for Players {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        action == Action::Tick;
         Ok(None)
     }
 
@@ -0,0 +1,45 @@
+use ratatui::{prelude::*, widgets::*};
+
+use super::Frame;
+use crate::utils::format_utc_date;
+
+/// A player's price at each recorded snapshot, as given by `PriceHistory::prices`, with `hint` along the bottom.
+pub fn draw(f: &mut Frame<'_>, area: Rect, name: &str, prices: &[(u64, i64)], hint: String) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(symbols::border::DOUBLE)
+        .title(format!(" {} price ", name))
+        .title_bottom(Line::from(hint).dim().right_aligned());
+    f.render_widget(Clear, area);
+    let (Some(&(first, _)), Some(&(last, _))) = (prices.first(), prices.last()) else {
+        f.render_widget(Paragraph::new("No prices recorded yet").block(block), area);
+        return;
+    };
+    if prices.len() < 2 {
+        let message = "Prices are saved each time tfpl starts or refreshes, so the chart fills in over the days";
+        f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }).block(block), area);
+        return;
+    }
+    let days = |taken: u64| (taken - first) as f64 / 86_400.0;
+    // A step at each change rather than a slope between snapshots
+    let mut points: Vec<(f64, f64)> = Vec::new();
+    for &(taken, cost) in prices {
+        let price = cost as f64 / 10.0;
+        if let Some(&(_, previous)) = points.last() {
+            points.push((days(taken), previous));
+        }
+        points.push((days(taken), price));
+    }
+    let low = prices.iter().map(|(_, c)| *c).min().unwrap_or(0) as f64 / 10.0 - 0.1;
+    let high = prices.iter().map(|(_, c)| *c).max().unwrap_or(0) as f64 / 10.0 + 0.1;
+    let dataset = Dataset::default().marker(symbols::Marker::Braille).graph_type(GraphType::Line).cyan().data(&points);
+    let chart = Chart::new(vec![dataset])
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, days(last).max(1.0 / 24.0)])
+                .labels([format_utc_date(first), format_utc_date(last)]),
+        )
+        .y_axis(Axis::default().bounds([low, high]).labels([format!("£{:.1}m", low), format!("£{:.1}m", high)]));
+    f.render_widget(chart, area);
+}
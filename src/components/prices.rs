@@ -0,0 +1,302 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use color_eyre::eyre::Result;
+use fpl_api::bootstrap::{BootstrapData, Element};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{price_chart, Component, Frame};
+use crate::{
+    action::Action,
+    config::Config,
+    data_source::Snapshot,
+    event::Event,
+    mode::Mode,
+    price_history::PriceHistory,
+    price_predictor::{self, Prediction},
+};
+
+/// Players listed on each side, closest to a change first.
+const LISTED: usize = 50;
+
+/// Which way a list of players is heading.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    #[default]
+    Risers,
+    Fallers,
+}
+
+/// Predicted price risers and fallers, with the manager's own players marked.
+pub struct Prices {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    elements: HashMap<i64, Element>,
+    team_names: HashMap<i64, String>,
+    total_players: i64,
+    prices: Arc<PriceHistory>,
+    own_manager_id: i64,
+    /// Element ids in the manager's latest picks.
+    owned: HashSet<i64>,
+    predictions: HashMap<i64, Prediction>,
+    risers: Vec<i64>,
+    fallers: Vec<i64>,
+    focus: Side,
+    risers_state: TableState,
+    fallers_state: TableState,
+    /// Element id of the player whose price chart is open.
+    chart: Option<i64>,
+}
+
+impl Prices {
+    pub fn new(snapshot: &Snapshot) -> Self {
+        let mut prices = Self {
+            command_tx: None,
+            config: Config::default(),
+            mode: Mode::Home,
+            elements: HashMap::new(),
+            team_names: HashMap::new(),
+            total_players: 0,
+            prices: Arc::default(),
+            own_manager_id: snapshot.manager.id,
+            owned: snapshot.gw_picks.picks.iter().map(|p| p.element).collect(),
+            predictions: HashMap::new(),
+            risers: Vec::new(),
+            fallers: Vec::new(),
+            focus: Side::Risers,
+            risers_state: TableState::default().with_selected(Some(0)),
+            fallers_state: TableState::default().with_selected(Some(0)),
+            chart: None,
+        };
+        prices.load(&snapshot.bootstrap);
+        prices
+    }
+
+    fn load(&mut self, bootstrap: &BootstrapData) {
+        self.elements = bootstrap.elements.iter().map(|e| (e.id, e.clone())).collect();
+        self.team_names = bootstrap.teams.iter().map(|t| (t.id, t.short_name.clone())).collect();
+        self.total_players = bootstrap.total_players;
+        self.predict();
+    }
+
+    fn predict(&mut self) {
+        self.predictions = price_predictor::predict(self.elements.values(), self.total_players, &self.prices);
+        let mut ranked: Vec<(i64, f64)> = self.predictions.iter().map(|(id, p)| (*id, p.progress)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.risers = ranked.iter().filter(|(_, p)| *p > 0.0).take(LISTED).map(|(id, _)| *id).collect();
+        self.fallers = ranked.iter().rev().filter(|(_, p)| *p < 0.0).take(LISTED).map(|(id, _)| *id).collect();
+    }
+
+    fn focused(&mut self) -> (&[i64], &mut TableState) {
+        match self.focus {
+            Side::Risers => (&self.risers, &mut self.risers_state),
+            Side::Fallers => (&self.fallers, &mut self.fallers_state),
+        }
+    }
+
+    fn selected_player(&mut self) -> Option<i64> {
+        let (rows, state) = self.focused();
+        state.selected().and_then(|i| rows.get(i)).copied()
+    }
+
+    /// Owned players close to a change, rises first.
+    fn owned_warnings(&self) -> Line<'static> {
+        let mut owned: Vec<(&Element, &Prediction)> = self
+            .owned
+            .iter()
+            .filter_map(|id| Some((self.elements.get(id)?, self.predictions.get(id)?)))
+            .filter(|(_, p)| p.indicator().is_some())
+            .collect();
+        owned.sort_by(|a, b| b.1.progress.total_cmp(&a.1.progress));
+        if owned.is_empty() {
+            return Line::from(" None of your players look close to a price change").dim();
+        }
+        let mut spans = vec![Span::raw(" Your players: ")];
+        for (e, p) in owned {
+            let Some((arrow, colour)) = p.indicator() else {
+                continue;
+            };
+            let text = format!("{} {} {:.0}%  ", e.web_name, arrow, p.progress.abs() * 100.0);
+            spans.push(Span::styled(text, colour));
+        }
+        Line::from(spans)
+    }
+
+    fn draw_table(&mut self, f: &mut Frame<'_>, area: Rect, side: Side) {
+        let header = Row::new(["", "Name", "Team", "£", "Own%", "Net", "Progress", "ETA"])
+            .style(Style::default().bg(Color::Indexed(127_u8)).fg(Color::White));
+        let (rows, title, colour) = match side {
+            Side::Risers => (&self.risers, " Likely risers ", Color::Green),
+            Side::Fallers => (&self.fallers, " Likely fallers ", Color::Red),
+        };
+        let rows =
+            rows.iter().filter_map(|id| Some((self.elements.get(id)?, self.predictions.get(id)?))).map(|(e, p)| {
+                let eta = p.hours_to_change().map_or(String::new(), |hours| {
+                    match hours {
+                        h if h < 48.0 => format!("{:.0}h", h),
+                        h => format!("{:.0}d", h / 24.0),
+                    }
+                });
+                let progress = Span::raw(format!("{:.0}%", p.progress.abs() * 100.0));
+                let progress = match p.indicator() {
+                    Some(_) => progress.style(colour),
+                    None => progress,
+                };
+                let owned = self.owned.contains(&e.id);
+                let row = Row::new([
+                    Cell::from(if owned { "●" } else { "" }),
+                    Cell::from(e.web_name.clone()),
+                    Cell::from(self.team_names.get(&e.team).cloned().unwrap_or_default()),
+                    Cell::from(Line::from(format!("{:.1}", e.now_cost as f64 / 10.0)).right_aligned()),
+                    Cell::from(Line::from(e.selected_by_percent.clone()).right_aligned()),
+                    Cell::from(Line::from(format!("{:+}", p.net_transfers)).right_aligned()),
+                    Cell::from(Line::from(progress).right_aligned()),
+                    Cell::from(Line::from(eta).right_aligned()),
+                ]);
+                if owned {
+                    row.add_modifier(Modifier::BOLD)
+                } else {
+                    row
+                }
+            });
+        let widths = [
+            Constraint::Length(1),
+            Constraint::Min(14),
+            Constraint::Length(4),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(4),
+        ];
+        let border = match self.focus == side {
+            true => Style::default().fg(colour),
+            false => Style::default(),
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(match self.focus == side {
+                true => Style::default().add_modifier(Modifier::REVERSED),
+                false => Style::default(),
+            })
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(title));
+        let state = match side {
+            Side::Risers => &mut self.risers_state,
+            Side::Fallers => &mut self.fallers_state,
+        };
+        f.render_stateful_widget(table, area, state);
+    }
+}
+
+impl Component for Prices {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>> {
+        let r = match event {
+            Some(Event::Refreshed(snapshot)) => {
+                if snapshot.manager.id == self.own_manager_id {
+                    self.owned = snapshot.gw_picks.picks.iter().map(|p| p.element).collect();
+                }
+                self.load(&snapshot.bootstrap);
+                None
+            },
+            Some(Event::PriceHistory(prices)) => {
+                self.prices = prices;
+                self.predict();
+                None
+            },
+            Some(Event::ProfileSwitched(_, profile)) if profile.manager_id != self.own_manager_id => {
+                self.own_manager_id = profile.manager_id;
+                self.owned.clear();
+                None
+            },
+            _ => None,
+        };
+        Ok(r)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::SwitchMode(mode) = action {
+            self.mode = mode;
+            return Ok(None);
+        }
+        if self.mode != Mode::Prices {
+            return Ok(None);
+        }
+        if self.chart.is_some() {
+            if action == Action::Escape {
+                self.chart = None;
+            }
+            return Ok(None);
+        }
+        match action {
+            Action::Escape => return Ok(Some(Action::SwitchMode(Mode::Home))),
+            Action::NextPane | Action::Left | Action::Right => {
+                self.focus = match self.focus {
+                    Side::Risers => Side::Fallers,
+                    Side::Fallers => Side::Risers,
+                };
+            },
+            Action::Up => self.focused().1.select_previous(),
+            Action::Down => self.focused().1.select_next(),
+            Action::Chart => self.chart = self.selected_player(),
+            _ => {},
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        if self.mode != Mode::Prices {
+            return Ok(());
+        }
+        f.render_widget(Clear, area);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(10), Constraint::Length(1)])
+            .split(area);
+        f.render_widget(self.owned_warnings(), layout[0]);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(layout[1]);
+        self.draw_table(f, columns[0], Side::Risers);
+        self.draw_table(f, columns[1], Side::Fallers);
+
+        let hints = |entries: &[(&[Action], &str)]| self.config.keybindings.hints(Mode::Prices, entries);
+        let footer = match self.prices.snapshots.is_empty() {
+            true => {
+                format!(
+                    " Predictions sharpen as prices are recorded on each start and refresh ·{}",
+                    hints(&[(&[Action::Escape], "back")])
+                )
+            },
+            false => {
+                format!(
+                    "{}· ● in your squad ·{}",
+                    hints(&[(&[Action::NextPane], "switch list"), (&[Action::Chart], "price chart")]),
+                    hints(&[(&[Action::Escape], "back")])
+                )
+            },
+        };
+        f.render_widget(Line::from(footer).dim(), layout[2]);
+
+        if let Some(element) = self.chart.and_then(|id| self.elements.get(&id)) {
+            let chart_area = Layout::default().constraints([Constraint::Percentage(100)]).margin(4).split(area)[0];
+            let hint = self.config.keybindings.hints(Mode::Prices, &[(&[Action::Escape], "close")]);
+            price_chart::draw(f, chart_area, &element.web_name, &self.prices.prices(element.id), hint);
+        }
+        Ok(())
+    }
+}
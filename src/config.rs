@@ -1,6 +1,11 @@
-use std::{collections::HashMap, fmt, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    ops::Bound::{Excluded, Unbounded},
+    path::PathBuf,
+};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use config::Value;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
@@ -21,6 +26,100 @@ pub struct AppConfig {
     pub _data_dir: PathBuf,
     #[serde(default)]
     pub _config_dir: PathBuf,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    /// How often, in seconds, live gameweek points are refetched.
+    #[serde(default = "default_live_refresh_secs")]
+    pub live_refresh_secs: u64,
+    /// How many gameweeks the fixture difficulty grid looks ahead.
+    #[serde(default = "default_fdr_gameweeks")]
+    pub fdr_gameweeks: i64,
+    /// How many gameweeks the expected points shown for players cover.
+    #[serde(default = "default_projection_gameweeks")]
+    pub projection_gameweeks: i64,
+    /// Managers to open tfpl for by name, with `--profile` or by switching in the TUI.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// The profile to open when neither `--manager-id` nor `--profile` is given.
+    #[serde(default)]
+    pub default_profile: Option<String>,
+}
+
+impl AppConfig {
+    /// The profile called `name`, or the default profile without a name, if there is one.
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<(String, Profile)>> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(Some((name.to_string(), profile.clone()))),
+            None => {
+                let names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                Err(eyre!("No profile called {} in the config, the profiles are: {}", name, names.join(", ")))
+            },
+        }
+    }
+
+    /// The profile after `current` in name order, going back round to the first.
+    pub fn next_profile(&self, current: Option<&str>) -> Option<(String, Profile)> {
+        let after = current.and_then(|current| self.profiles.range::<str, _>((Excluded(current), Unbounded)).next());
+        after.or_else(|| self.profiles.iter().next()).map(|(name, profile)| (name.clone(), profile.clone()))
+    }
+}
+
+/// A manager, with the leagues and rivals they follow.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub manager_id: i64,
+    /// Leagues listed first, and added to the list when the manager isn't in them.
+    pub leagues: Vec<i64>,
+    /// Which of `leagues` are head-to-head, since there's no telling for those the manager isn't in.
+    pub h2h_leagues: Vec<i64>,
+    /// Managers highlighted in league standings and listed to jump straight to.
+    pub rivals: Vec<i64>,
+}
+
+fn default_live_refresh_secs() -> u64 {
+    60
+}
+
+fn default_fdr_gameweeks() -> i64 {
+    6
+}
+
+fn default_projection_gameweeks() -> i64 {
+    5
+}
+
+/// Settings for the on-disk API response cache. TTLs are in seconds.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// Defaults to `cache` under the data directory.
+    pub dir: Option<PathBuf>,
+    pub bootstrap_ttl_secs: u64,
+    pub fixtures_ttl_secs: u64,
+    pub manager_ttl_secs: u64,
+    pub picks_ttl_secs: u64,
+    pub live_ttl_secs: u64,
+    pub leagues_ttl_secs: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            bootstrap_ttl_secs: 10 * 60,
+            fixtures_ttl_secs: 24 * 60 * 60,
+            manager_ttl_secs: 5 * 60,
+            picks_ttl_secs: 5 * 60,
+            live_ttl_secs: 30,
+            leagues_ttl_secs: 5 * 60,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -82,6 +181,43 @@ impl Config {
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
 pub struct KeyBindings(pub HashMap<Mode, HashMap<Vec<KeyEvent>, Action>>);
 
+impl KeyBindings {
+    /// The action for the longest sequence the recent `keys` end with, with the mode's own bindings taking
+    /// precedence over global ones.
+    pub fn action(&self, mode: Mode, keys: &[KeyEvent]) -> Option<&Action> {
+        (0..keys.len()).find_map(|start| {
+            [mode, Mode::Global]
+                .iter()
+                .find_map(|mode| self.get(mode).and_then(|bindings| bindings.get(&keys[start..])))
+        })
+    }
+
+    /// The shortest key sequence that triggers `action` in `mode`, e.g. `<s>`, for showing in hints.
+    pub fn keys(&self, mode: Mode, action: &Action) -> Option<String> {
+        [mode, Mode::Global]
+            .iter()
+            .filter_map(|mode| self.get(mode))
+            .flat_map(|bindings| bindings.iter())
+            .filter(|(keys, bound)| *bound == action && self.action(mode, keys) == Some(action))
+            .map(|(keys, _)| key_sequence_to_string(keys))
+            .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))
+    }
+
+    /// A hint line such as ` <s> sell · <[>/<]> gameweek `, built from whatever keys are bound in `mode`.
+    ///
+    /// Each entry's actions have their keys joined with `/`; entries with an unbound action are left out.
+    pub fn hints(&self, mode: Mode, entries: &[(&[Action], &str)]) -> String {
+        let hints: Vec<String> = entries
+            .iter()
+            .filter_map(|(actions, label)| {
+                let keys = actions.iter().map(|action| self.keys(mode, action)).collect::<Option<Vec<_>>>()?;
+                Some(format!("{} {}", keys.join("/"), label))
+            })
+            .collect();
+        format!(" {} ", hints.join(" · "))
+    }
+}
+
 impl<'de> Deserialize<'de> for KeyBindings {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -201,7 +337,7 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
             char = format!("f({c})");
             &char
         },
-        KeyCode::Char(c) if c == ' ' => "space",
+        KeyCode::Char(' ') => "space",
         KeyCode::Char(c) => {
             char = c.to_string();
             &char
@@ -243,6 +379,11 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
+/// The reverse of [`parse_key_sequence`], e.g. `<g><g>` or `<ctrl-c>`.
+pub fn key_sequence_to_string(keys: &[KeyEvent]) -> String {
+    keys.iter().map(|key| format!("<{}>", key_event_to_string(key))).collect()
+}
+
 pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     if raw.chars().filter(|c| *c == '>').count() != raw.chars().filter(|c| *c == '<').count() {
         return Err(format!("Unable to parse `{}`", raw));
@@ -444,9 +585,84 @@ mod tests {
     fn test_config() -> Result<()> {
         let c = Config::new()?;
         assert_eq!(
-            c.keybindings.get(&Mode::Home).unwrap().get(&parse_key_sequence("<q>").unwrap_or_default()).unwrap(),
+            c.keybindings.get(&Mode::Global).unwrap().get(&parse_key_sequence("<q>").unwrap_or_default()).unwrap(),
             &Action::Quit
         );
+        assert_eq!(
+            c.keybindings.action(Mode::Browser, &parse_key_sequence("<Shift-o>").unwrap()),
+            Some(&Action::ReverseSort)
+        );
+        assert_eq!(
+            c.keybindings.action(Mode::Leagues, &parse_key_sequence("<3>").unwrap()),
+            Some(&Action::SwitchMode(Mode::Leagues))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_sequences() -> Result<()> {
+        let c: Config = json5::from_str(
+            r#"{
+                "keybindings": {
+                    "Global": { "<q>": "Quit", "<g><g>": "Up", "<j>": "Down" },
+                    "Planner": { "<j>": "Sell" },
+                },
+            }"#,
+        )?;
+        let keys = |raw| parse_key_sequence(raw).unwrap();
+        assert_eq!(c.keybindings.action(Mode::Home, &keys("<x><q>")), Some(&Action::Quit));
+        assert_eq!(c.keybindings.action(Mode::Home, &keys("<x><g><g>")), Some(&Action::Up));
+        assert_eq!(c.keybindings.action(Mode::Home, &keys("<g>")), None);
+        assert_eq!(c.keybindings.action(Mode::Home, &keys("<j>")), Some(&Action::Down));
+        assert_eq!(c.keybindings.action(Mode::Planner, &keys("<j>")), Some(&Action::Sell));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hints() -> Result<()> {
+        let c: Config = json5::from_str(
+            r#"{
+                "keybindings": {
+                    "Global": { "<q>": "Quit", "<Ctrl-c>": "Quit", "<j>": "Down", "<[>": "PreviousGameweek", "<]>": "NextGameweek" },
+                    "Planner": { "<j>": "Sell", "<z>": "Undo" },
+                },
+            }"#,
+        )?;
+        let kb = &c.keybindings;
+        assert_eq!(kb.keys(Mode::Home, &Action::Quit), Some("<q>".to_string()));
+        assert_eq!(kb.keys(Mode::Home, &Action::Down), Some("<j>".to_string()));
+        // Overridden by the planner's own binding
+        assert_eq!(kb.keys(Mode::Planner, &Action::Down), None);
+        assert_eq!(
+            kb.hints(Mode::Planner, &[
+                (&[Action::Sell], "sell"),
+                (&[Action::Undo], "undo"),
+                (&[Action::SaveDraft], "save"),
+                (&[Action::PreviousGameweek, Action::NextGameweek], "gameweek"),
+            ]),
+            " <j> sell · <z> undo · <[>/<]> gameweek "
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_profiles() -> Result<()> {
+        let c: Config = json5::from_str(
+            r#"{
+                "profiles": {
+                    "me": { "manager_id": 1 },
+                    "office": { "manager_id": 2, "leagues": [3, 5], "h2h_leagues": [5], "rivals": [4] },
+                },
+                "default_profile": "office",
+            }"#,
+        )?;
+        let office = Profile { manager_id: 2, leagues: vec![3, 5], h2h_leagues: vec![5], rivals: vec![4] };
+        assert_eq!(c.config.profile(None)?, Some(("office".to_string(), office.clone())));
+        assert_eq!(c.config.profile(Some("me"))?.map(|(_, p)| p.manager_id), Some(1));
+        assert!(c.config.profile(Some("nobody")).is_err());
+        assert_eq!(c.config.next_profile(Some("me")), Some(("office".to_string(), office)));
+        assert_eq!(c.config.next_profile(Some("office")).map(|(name, _)| name), Some("me".to_string()));
+        assert_eq!(c.config.next_profile(None).map(|(name, _)| name), Some("me".to_string()));
         Ok(())
     }
 
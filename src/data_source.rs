@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use fpl_api::{
+    bootstrap::BootstrapData,
+    fixture::Fixtures,
+    manager::{GWTeam, Manager},
+    players::GWLiveData,
+};
+use futures::future::BoxFuture;
+
+use crate::{
+    api::{ClassicStandings, H2HMatches, H2HStandings, ManagerHistory},
+    cli::Cli,
+    config::Config,
+    utils::get_data_dir,
+};
+
+pub mod cache;
+pub mod file;
+pub mod live;
+
+pub use cache::CachedDataSource;
+pub use file::FileDataSource;
+pub use live::LiveDataSource;
+
+/// An FPL API endpoint the app reads from.
+///
+/// Every [`DataSource`] agrees on the same endpoints, which is what lets a directory of recorded
+/// responses stand in for the live API.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Bootstrap,
+    Fixtures,
+    Manager(String),
+    Picks { manager_id: String, gameweek: i64 },
+    Live(i64),
+    History(String),
+    ClassicStandings { league_id: i64, page: i64 },
+    H2HStandings { league_id: i64, page: i64 },
+    H2HMatches { league_id: i64, gameweek: i64 },
+}
+
+impl Endpoint {
+    /// Path of the endpoint relative to the API root, without the trailing slash.
+    pub fn path(&self) -> String {
+        match self {
+            Endpoint::Bootstrap => "bootstrap-static".to_string(),
+            Endpoint::Fixtures => "fixtures".to_string(),
+            Endpoint::Manager(manager_id) => format!("entry/{}", manager_id),
+            Endpoint::Picks { manager_id, gameweek } => format!("entry/{}/event/{}/picks", manager_id, gameweek),
+            Endpoint::Live(gameweek) => format!("event/{}/live", gameweek),
+            Endpoint::History(manager_id) => format!("entry/{}/history", manager_id),
+            Endpoint::ClassicStandings { league_id, .. } => format!("leagues-classic/{}/standings", league_id),
+            Endpoint::H2HStandings { league_id, .. } => format!("leagues-h2h/{}/standings", league_id),
+            Endpoint::H2HMatches { league_id, .. } => format!("leagues-h2h-matches/league/{}", league_id),
+        }
+    }
+
+    /// Query string for the live API, including the leading `?`.
+    pub fn query(&self) -> String {
+        match self {
+            Endpoint::ClassicStandings { page, .. } | Endpoint::H2HStandings { page, .. } => {
+                format!("?page_standings={}", page)
+            },
+            // Only the first page of matches is read, which is plenty for a mini-league
+            Endpoint::H2HMatches { gameweek, .. } => format!("?page=1&event={}", gameweek),
+            _ => String::new(),
+        }
+    }
+
+    /// Where the response is kept in a recording or cache directory, without the extension.
+    ///
+    /// This is the endpoint's path, plus the page for paginated endpoints.
+    pub fn key(&self) -> String {
+        match self {
+            Endpoint::ClassicStandings { page, .. } | Endpoint::H2HStandings { page, .. } => {
+                format!("{}/page-{}", self.path(), page)
+            },
+            Endpoint::H2HMatches { gameweek, .. } => format!("{}/event-{}", self.path(), gameweek),
+            _ => self.path(),
+        }
+    }
+}
+
+/// Where the app gets its FPL data from.
+///
+/// The methods return boxed futures so that a source can be shared as an `Arc<dyn DataSource>`
+/// between the app and the background tasks it spawns.
+pub trait DataSource: Send + Sync {
+    fn get_bootstrap_data(&self) -> BoxFuture<'_, Result<BootstrapData>>;
+
+    fn get_fixtures(&self) -> BoxFuture<'_, Result<Fixtures>>;
+
+    fn get_manager_details<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<Manager>>;
+
+    fn get_manager_team_for_gw<'a>(&'a self, manager_id: &'a str, gameweek: i64) -> BoxFuture<'a, Result<GWTeam>>;
+
+    fn get_gw_live_data(&self, gameweek: i64) -> BoxFuture<'_, Result<GWLiveData>>;
+
+    fn get_manager_history<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<ManagerHistory>>;
+
+    fn get_classic_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<ClassicStandings>>;
+
+    fn get_h2h_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<H2HStandings>>;
+
+    fn get_h2h_matches(&self, league_id: i64, gameweek: i64) -> BoxFuture<'_, Result<H2HMatches>>;
+
+    /// Whether any of the data last returned was served from a stale copy after a failed fetch.
+    fn is_stale(&self) -> bool {
+        false
+    }
+
+    /// Treat everything fetched so far as out of date, so the next requests go upstream.
+    fn expire(&self) {
+    }
+
+    /// Whether the source reads recorded responses rather than going over the network.
+    fn is_offline(&self) -> bool {
+        false
+    }
+}
+
+/// Everything the pitch view needs for one manager's gameweek.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub bootstrap: BootstrapData,
+    pub manager: Manager,
+    pub fixtures: Fixtures,
+    pub gw_picks: GWTeam,
+    /// Whether any of the above was served stale after a failed fetch.
+    pub stale: bool,
+}
+
+impl Snapshot {
+    /// Load the manager's picks for `gameweek`, or their current gameweek, along with the data around them.
+    pub async fn load(data_source: &dyn DataSource, manager_id: &str, gameweek: Option<i64>) -> Result<Self> {
+        let (bootstrap, manager, fixtures) = futures::try_join!(
+            data_source.get_bootstrap_data(),
+            data_source.get_manager_details(manager_id),
+            data_source.get_fixtures(),
+        )?;
+        let gw_picks =
+            data_source.get_manager_team_for_gw(manager_id, gameweek.unwrap_or(manager.current_event)).await?;
+        Ok(Self { bootstrap, manager, fixtures, gw_picks, stale: data_source.is_stale() })
+    }
+}
+
+/// Picks the data source requested on the command line.
+///
+/// `--data-dir` or `--offline` switch to recorded responses, with `--offline` alone reading from
+/// the `responses` directory under the data dir. Otherwise the live FPL API is used, behind the
+/// on-disk cache unless it is disabled in the config.
+pub fn from_args(args: &Cli, config: &Config) -> Arc<dyn DataSource> {
+    match (&args.data_dir, args.offline) {
+        (Some(dir), _) => Arc::new(FileDataSource::new(dir.clone())),
+        (None, true) => Arc::new(FileDataSource::new(get_data_dir().join("responses"))),
+        (None, false) => {
+            let live = Arc::new(LiveDataSource::new());
+            let cache = &config.config.cache;
+            if !cache.enabled {
+                return live;
+            }
+            let dir = cache.dir.clone().unwrap_or_else(|| config.config._data_dir.join("cache"));
+            Arc::new(CachedDataSource::new(live, dir, cache.clone()))
+        },
+    }
+}
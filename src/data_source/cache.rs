@@ -0,0 +1,317 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::Result;
+use fpl_api::{
+    bootstrap::BootstrapData,
+    fixture::Fixtures,
+    manager::{GWTeam, Manager},
+    players::GWLiveData,
+};
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{DataSource, Endpoint};
+use crate::{
+    api::{ClassicStandings, H2HMatches, H2HStandings, ManagerHistory},
+    config::CacheConfig,
+};
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<T> {
+    /// Milliseconds since the unix epoch at which `data` was fetched.
+    fetched_at: u64,
+    data: T,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+/// Wraps another source and keeps each endpoint's latest response on disk.
+///
+/// Responses younger than the endpoint's TTL are served without touching the inner source. When
+/// the inner source fails, the last stored response is served instead, however old, and the
+/// source reports itself as stale until that endpoint is fetched successfully again.
+pub struct CachedDataSource {
+    inner: Arc<dyn DataSource>,
+    dir: PathBuf,
+    config: CacheConfig,
+    stale: Mutex<HashSet<Endpoint>>,
+    /// Entries fetched at or before this time (in unix milliseconds) are expired regardless of their TTL.
+    expired_before: AtomicU64,
+}
+
+impl CachedDataSource {
+    pub fn new(inner: Arc<dyn DataSource>, dir: PathBuf, config: CacheConfig) -> Self {
+        Self { inner, dir, config, stale: Mutex::new(HashSet::new()), expired_before: AtomicU64::new(0) }
+    }
+
+    fn path_for(&self, endpoint: &Endpoint) -> PathBuf {
+        self.dir.join(format!("{}.json", endpoint.key()))
+    }
+
+    fn ttl(&self, endpoint: &Endpoint) -> Duration {
+        let secs = match endpoint {
+            Endpoint::Bootstrap => self.config.bootstrap_ttl_secs,
+            Endpoint::Fixtures => self.config.fixtures_ttl_secs,
+            Endpoint::Manager(_) | Endpoint::History(_) => self.config.manager_ttl_secs,
+            Endpoint::ClassicStandings { .. } | Endpoint::H2HStandings { .. } | Endpoint::H2HMatches { .. } => {
+                self.config.leagues_ttl_secs
+            },
+            Endpoint::Picks { .. } => self.config.picks_ttl_secs,
+            Endpoint::Live(_) => self.config.live_ttl_secs,
+        };
+        Duration::from_secs(secs)
+    }
+
+    async fn load<T: DeserializeOwned>(&self, endpoint: &Endpoint) -> Option<CacheEntry<T>> {
+        let raw = tokio::fs::read(self.path_for(endpoint)).await.ok()?;
+        match serde_json::from_slice(&raw) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Ignoring unreadable cache entry for {}: {}", endpoint.key(), e);
+                None
+            },
+        }
+    }
+
+    async fn store<T: Serialize>(&self, endpoint: &Endpoint, data: &T) -> Result<()> {
+        let path = self.path_for(endpoint);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let entry = CacheEntry { fetched_at: now_millis(), data };
+        tokio::fs::write(path, serde_json::to_vec(&entry)?).await?;
+        Ok(())
+    }
+
+    fn is_fresh<T>(&self, endpoint: &Endpoint, entry: &CacheEntry<T>) -> bool {
+        entry.fetched_at > self.expired_before.load(Ordering::SeqCst)
+            && now_millis().saturating_sub(entry.fetched_at) < self.ttl(endpoint).as_millis() as u64
+    }
+
+    fn mark_stale(&self, endpoint: &Endpoint, stale: bool) {
+        let mut set = self.stale.lock().unwrap();
+        if stale {
+            set.insert(endpoint.clone());
+        } else {
+            set.remove(endpoint);
+        }
+    }
+
+    async fn cached<T>(&self, endpoint: Endpoint, fetch: BoxFuture<'_, Result<T>>) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned + Send,
+    {
+        let entry = match self.load::<T>(&endpoint).await {
+            Some(entry) if self.is_fresh(&endpoint, &entry) => {
+                self.mark_stale(&endpoint, false);
+                return Ok(entry.data);
+            },
+            entry => entry,
+        };
+        match fetch.await {
+            Ok(data) => {
+                if let Err(e) = self.store(&endpoint, &data).await {
+                    log::warn!("Unable to cache {}: {}", endpoint.key(), e);
+                }
+                self.mark_stale(&endpoint, false);
+                Ok(data)
+            },
+            Err(e) => {
+                match entry {
+                    Some(entry) => {
+                        log::warn!("Serving stale {} after fetch failed: {}", endpoint.key(), e);
+                        self.mark_stale(&endpoint, true);
+                        Ok(entry.data)
+                    },
+                    None => Err(e),
+                }
+            },
+        }
+    }
+}
+
+impl DataSource for CachedDataSource {
+    fn get_bootstrap_data(&self) -> BoxFuture<'_, Result<BootstrapData>> {
+        Box::pin(self.cached(Endpoint::Bootstrap, self.inner.get_bootstrap_data()))
+    }
+
+    fn get_fixtures(&self) -> BoxFuture<'_, Result<Fixtures>> {
+        Box::pin(self.cached(Endpoint::Fixtures, self.inner.get_fixtures()))
+    }
+
+    fn get_manager_details<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<Manager>> {
+        Box::pin(self.cached(Endpoint::Manager(manager_id.to_string()), self.inner.get_manager_details(manager_id)))
+    }
+
+    fn get_manager_team_for_gw<'a>(&'a self, manager_id: &'a str, gameweek: i64) -> BoxFuture<'a, Result<GWTeam>> {
+        Box::pin(self.cached(
+            Endpoint::Picks { manager_id: manager_id.to_string(), gameweek },
+            self.inner.get_manager_team_for_gw(manager_id, gameweek),
+        ))
+    }
+
+    fn get_gw_live_data(&self, gameweek: i64) -> BoxFuture<'_, Result<GWLiveData>> {
+        Box::pin(self.cached(Endpoint::Live(gameweek), self.inner.get_gw_live_data(gameweek)))
+    }
+
+    fn get_manager_history<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<ManagerHistory>> {
+        Box::pin(self.cached(Endpoint::History(manager_id.to_string()), self.inner.get_manager_history(manager_id)))
+    }
+
+    fn get_classic_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<ClassicStandings>> {
+        Box::pin(
+            self.cached(
+                Endpoint::ClassicStandings { league_id, page },
+                self.inner.get_classic_standings(league_id, page),
+            ),
+        )
+    }
+
+    fn get_h2h_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<H2HStandings>> {
+        Box::pin(self.cached(Endpoint::H2HStandings { league_id, page }, self.inner.get_h2h_standings(league_id, page)))
+    }
+
+    fn get_h2h_matches(&self, league_id: i64, gameweek: i64) -> BoxFuture<'_, Result<H2HMatches>> {
+        Box::pin(
+            self.cached(Endpoint::H2HMatches { league_id, gameweek }, self.inner.get_h2h_matches(league_id, gameweek)),
+        )
+    }
+
+    fn is_stale(&self) -> bool {
+        !self.stale.lock().unwrap().is_empty()
+    }
+
+    fn expire(&self) {
+        self.expired_before.store(now_millis(), Ordering::SeqCst);
+        self.inner.expire();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+    use color_eyre::eyre::eyre;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Serves a fixed manager, or fails every request once `online` is cleared.
+    struct Upstream {
+        online: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    impl Upstream {
+        fn new() -> Arc<Self> {
+            Arc::new(Self { online: true.into(), calls: AtomicUsize::new(0) })
+        }
+
+        fn respond<T: Send + 'static>(&self, value: T) -> BoxFuture<'_, Result<T>> {
+            Box::pin(async move {
+                self.calls.fetch_add(1, Ordering::SeqCst);
+                match self.online.load(Ordering::SeqCst) {
+                    true => Ok(value),
+                    false => Err(eyre!("offline")),
+                }
+            })
+        }
+    }
+
+    impl DataSource for Upstream {
+        fn get_bootstrap_data(&self) -> BoxFuture<'_, Result<BootstrapData>> {
+            self.respond(BootstrapData::default())
+        }
+
+        fn get_fixtures(&self) -> BoxFuture<'_, Result<Fixtures>> {
+            self.respond(Fixtures::default())
+        }
+
+        fn get_manager_details<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<Manager>> {
+            self.respond(Manager { name: format!("Manager {}", manager_id), ..Default::default() })
+        }
+
+        fn get_manager_team_for_gw<'a>(&'a self, _: &'a str, _: i64) -> BoxFuture<'a, Result<GWTeam>> {
+            self.respond(GWTeam::default())
+        }
+
+        fn get_gw_live_data(&self, _: i64) -> BoxFuture<'_, Result<GWLiveData>> {
+            self.respond(GWLiveData::default())
+        }
+
+        fn get_manager_history<'a>(&'a self, _: &'a str) -> BoxFuture<'a, Result<ManagerHistory>> {
+            self.respond(ManagerHistory::default())
+        }
+
+        fn get_classic_standings(&self, _: i64, _: i64) -> BoxFuture<'_, Result<ClassicStandings>> {
+            self.respond(ClassicStandings::default())
+        }
+
+        fn get_h2h_standings(&self, _: i64, _: i64) -> BoxFuture<'_, Result<H2HStandings>> {
+            self.respond(H2HStandings::default())
+        }
+
+        fn get_h2h_matches(&self, _: i64, _: i64) -> BoxFuture<'_, Result<H2HMatches>> {
+            self.respond(H2HMatches::default())
+        }
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tfpl-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_fresh_entries_skip_upstream() -> Result<()> {
+        let upstream = Upstream::new();
+        let cache = CachedDataSource::new(upstream.clone(), cache_dir("fresh"), CacheConfig::default());
+
+        cache.get_manager_details("1").await?;
+        let manager = cache.get_manager_details("1").await?;
+
+        assert_eq!(manager.name, "Manager 1");
+        assert_eq!(upstream.calls.load(Ordering::SeqCst), 1);
+        assert!(!cache.is_stale());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serves_stale_copy_when_upstream_fails() -> Result<()> {
+        let upstream = Upstream::new();
+        let config = CacheConfig { manager_ttl_secs: 0, ..Default::default() };
+        let cache = CachedDataSource::new(upstream.clone(), cache_dir("stale"), config);
+
+        cache.get_manager_details("2").await?;
+        upstream.online.store(false, Ordering::SeqCst);
+        let manager = cache.get_manager_details("2").await?;
+
+        assert_eq!(manager.name, "Manager 2");
+        assert!(cache.is_stale());
+        assert!(cache.get_manager_details("3").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expire_forces_refetch() -> Result<()> {
+        let upstream = Upstream::new();
+        let cache = CachedDataSource::new(upstream.clone(), cache_dir("expire"), CacheConfig::default());
+
+        cache.get_fixtures().await?;
+        cache.expire();
+        cache.get_fixtures().await?;
+
+        assert_eq!(upstream.calls.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+}
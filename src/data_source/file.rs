@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, WrapErr};
+use fpl_api::{
+    bootstrap::BootstrapData,
+    fixture::Fixtures,
+    manager::{GWTeam, Manager},
+    players::GWLiveData,
+};
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+
+use super::{DataSource, Endpoint};
+use crate::api::{ClassicStandings, H2HMatches, H2HStandings, ManagerHistory};
+
+/// Reads recorded API responses from a directory.
+///
+/// Each endpoint is stored as `<dir>/<endpoint key>.json`, so a recording looks like
+///
+/// ```text
+/// bootstrap-static.json
+/// fixtures.json
+/// entry/123.json
+/// entry/123/event/10/picks.json
+/// entry/123/history.json
+/// event/10/live.json
+/// leagues-classic/456/standings/page-1.json
+/// leagues-h2h/789/standings/page-1.json
+/// leagues-h2h-matches/league/789/event-10.json
+/// ```
+pub struct FileDataSource {
+    dir: PathBuf,
+}
+
+impl FileDataSource {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn path_for(&self, endpoint: &Endpoint) -> PathBuf {
+        self.dir.join(format!("{}.json", endpoint.key()))
+    }
+
+    async fn read<T: DeserializeOwned>(&self, endpoint: Endpoint) -> Result<T> {
+        let path = self.path_for(&endpoint);
+        let raw =
+            tokio::fs::read(&path).await.wrap_err_with(|| format!("No recorded response at {}", path.display()))?;
+        serde_json::from_slice(&raw).wrap_err_with(|| format!("Unable to parse {}", path.display()))
+    }
+}
+
+impl DataSource for FileDataSource {
+    fn get_bootstrap_data(&self) -> BoxFuture<'_, Result<BootstrapData>> {
+        Box::pin(self.read(Endpoint::Bootstrap))
+    }
+
+    fn get_fixtures(&self) -> BoxFuture<'_, Result<Fixtures>> {
+        Box::pin(self.read(Endpoint::Fixtures))
+    }
+
+    fn get_manager_details<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<Manager>> {
+        Box::pin(self.read(Endpoint::Manager(manager_id.to_string())))
+    }
+
+    fn get_manager_team_for_gw<'a>(&'a self, manager_id: &'a str, gameweek: i64) -> BoxFuture<'a, Result<GWTeam>> {
+        Box::pin(self.read(Endpoint::Picks { manager_id: manager_id.to_string(), gameweek }))
+    }
+
+    fn get_gw_live_data(&self, gameweek: i64) -> BoxFuture<'_, Result<GWLiveData>> {
+        Box::pin(self.read(Endpoint::Live(gameweek)))
+    }
+
+    fn get_manager_history<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<ManagerHistory>> {
+        Box::pin(self.read(Endpoint::History(manager_id.to_string())))
+    }
+
+    fn get_classic_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<ClassicStandings>> {
+        Box::pin(self.read(Endpoint::ClassicStandings { league_id, page }))
+    }
+
+    fn get_h2h_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<H2HStandings>> {
+        Box::pin(self.read(Endpoint::H2HStandings { league_id, page }))
+    }
+
+    fn get_h2h_matches(&self, league_id: i64, gameweek: i64) -> BoxFuture<'_, Result<H2HMatches>> {
+        Box::pin(self.read(Endpoint::H2HMatches { league_id, gameweek }))
+    }
+
+    fn is_offline(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_api::manager::Pick;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn recording_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tfpl-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn record<T: serde::Serialize>(source: &FileDataSource, endpoint: Endpoint, value: &T) {
+        let path = source.path_for(&endpoint);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, serde_json::to_vec(value).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_recorded_responses() -> Result<()> {
+        let source = FileDataSource::new(recording_dir("recorded"));
+        let manager = Manager { id: 42, name: "Recorded FC".to_string(), current_event: 7, ..Default::default() };
+        let picks = GWTeam {
+            picks: vec![Pick { element: 1, position: 1, multiplier: 1, ..Default::default() }],
+            ..Default::default()
+        };
+        record(&source, Endpoint::Manager("42".to_string()), &manager);
+        record(&source, Endpoint::Picks { manager_id: "42".to_string(), gameweek: 7 }, &picks);
+
+        assert_eq!(source.get_manager_details("42").await?, manager);
+        assert_eq!(source.get_manager_team_for_gw("42", 7).await?, picks);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_missing_recording_names_the_file() {
+        let source = FileDataSource::new(recording_dir("missing"));
+        let err = source.get_fixtures().await.unwrap_err();
+        assert!(err.to_string().contains("fixtures.json"));
+    }
+}
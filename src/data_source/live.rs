@@ -0,0 +1,79 @@
+use color_eyre::eyre::{Result, WrapErr};
+use fpl_api::{
+    bootstrap::BootstrapData,
+    fixture::Fixtures,
+    manager::{GWTeam, Manager},
+    players::GWLiveData,
+    FPLClient,
+};
+use futures::future::BoxFuture;
+use serde::de::DeserializeOwned;
+
+use super::{DataSource, Endpoint};
+use crate::api::{ClassicStandings, H2HMatches, H2HStandings, ManagerHistory};
+
+const API_URL: &str = "https://fantasy.premierleague.com/api";
+
+/// Reads from the FPL API over the network.
+pub struct LiveDataSource {
+    client: FPLClient,
+}
+
+impl Default for LiveDataSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiveDataSource {
+    pub fn new() -> Self {
+        Self { client: FPLClient::new() }
+    }
+}
+
+impl LiveDataSource {
+    /// Fetch an endpoint that `FPLClient` has no method for.
+    async fn get_json<T: DeserializeOwned>(&self, endpoint: Endpoint) -> Result<T> {
+        let url = format!("{}/{}/{}", API_URL, endpoint.path(), endpoint.query());
+        let bytes = reqwest::get(&url).await?.error_for_status()?.bytes().await?;
+        serde_json::from_slice(&bytes).wrap_err_with(|| format!("Unable to parse response from {}", url))
+    }
+}
+
+impl DataSource for LiveDataSource {
+    fn get_bootstrap_data(&self) -> BoxFuture<'_, Result<BootstrapData>> {
+        Box::pin(async move { Ok(self.client.get_bootstrap_data().await?) })
+    }
+
+    fn get_fixtures(&self) -> BoxFuture<'_, Result<Fixtures>> {
+        Box::pin(async move { Ok(self.client.get_fixtures().await?) })
+    }
+
+    fn get_manager_details<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<Manager>> {
+        Box::pin(async move { Ok(self.client.get_manager_details(manager_id).await?) })
+    }
+
+    fn get_manager_team_for_gw<'a>(&'a self, manager_id: &'a str, gameweek: i64) -> BoxFuture<'a, Result<GWTeam>> {
+        Box::pin(async move { Ok(self.client.get_manager_team_for_gw(manager_id, &gameweek.to_string()).await?) })
+    }
+
+    fn get_gw_live_data(&self, gameweek: i64) -> BoxFuture<'_, Result<GWLiveData>> {
+        Box::pin(async move { Ok(self.client.get_gw_live_data(&gameweek.to_string()).await?) })
+    }
+
+    fn get_manager_history<'a>(&'a self, manager_id: &'a str) -> BoxFuture<'a, Result<ManagerHistory>> {
+        Box::pin(self.get_json(Endpoint::History(manager_id.to_string())))
+    }
+
+    fn get_classic_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<ClassicStandings>> {
+        Box::pin(self.get_json(Endpoint::ClassicStandings { league_id, page }))
+    }
+
+    fn get_h2h_standings(&self, league_id: i64, page: i64) -> BoxFuture<'_, Result<H2HStandings>> {
+        Box::pin(self.get_json(Endpoint::H2HStandings { league_id, page }))
+    }
+
+    fn get_h2h_matches(&self, league_id: i64, gameweek: i64) -> BoxFuture<'_, Result<H2HMatches>> {
+        Box::pin(self.get_json(Endpoint::H2HMatches { league_id, gameweek }))
+    }
+}
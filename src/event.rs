@@ -1,7 +1,17 @@
+use std::sync::Arc;
+
 use crossterm::event::{KeyEvent, KeyEventKind, MouseEvent};
+use fpl_api::{manager::GWTeam, players::GWLiveData};
 use image::DynamicImage;
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    api::{ClassicStandings, H2HMatches, H2HStandings, ManagerHistory},
+    config::Profile,
+    data_source::Snapshot,
+    price_history::PriceHistory,
+};
+
 #[derive(Clone, Debug)]
 pub enum Event {
     Init,
@@ -17,4 +27,16 @@ pub enum Event {
     Mouse(MouseEvent),
     Resize(u16, u16),
     PlayerImage(i64, DynamicImage),
+    LiveData(i64, GWLiveData),
+    Refreshed(Box<Snapshot>),
+    GameweekLoaded(i64, Box<GWTeam>, Option<GWLiveData>),
+    /// A manager's season history, with their id.
+    ManagerHistory(i64, Box<ManagerHistory>),
+    ClassicStandings(i64, Box<ClassicStandings>),
+    H2HStandings(i64, Box<H2HStandings>),
+    H2HMatches(i64, i64, Box<H2HMatches>),
+    MatchupLoaded(i64, [i64; 2], Box<[GWTeam; 2]>, Option<GWLiveData>),
+    PriceHistory(Arc<PriceHistory>),
+    /// The profile now in use, sent at startup and when switching, before its team arrives.
+    ProfileSwitched(String, Profile),
 }
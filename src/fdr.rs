@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use fpl_api::fixture::Fixture;
+use ratatui::style::Color;
+
+/// One of a team's fixtures, seen from that team's side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamFixture {
+    pub opponent: i64,
+    pub is_home: bool,
+    /// Fixture difficulty rating from 1 (easiest) to 5 (hardest).
+    pub difficulty: i64,
+}
+
+/// Each team's fixtures in gameweeks `first..first + count`, one entry per gameweek.
+///
+/// A gameweek with no fixtures is a blank for that team, and one with two or more is a double.
+pub fn schedule(fixtures: &[Fixture], first: i64, count: i64) -> HashMap<i64, Vec<Vec<TeamFixture>>> {
+    let mut schedule: HashMap<i64, Vec<Vec<TeamFixture>>> = HashMap::new();
+    for f in fixtures.iter().filter(|f| (first..first + count).contains(&f.event)) {
+        let slot = (f.event - first) as usize;
+        let sides = [
+            (f.team_h, TeamFixture { opponent: f.team_a, is_home: true, difficulty: f.team_h_difficulty }),
+            (f.team_a, TeamFixture { opponent: f.team_h, is_home: false, difficulty: f.team_a_difficulty }),
+        ];
+        for (team, fixture) in sides {
+            let gameweeks = schedule.entry(team).or_insert_with(|| vec![Vec::new(); count as usize]);
+            gameweeks[slot].push(fixture);
+        }
+    }
+    schedule
+}
+
+/// The colours the FPL site uses for each difficulty rating, as (background, foreground).
+pub fn difficulty_colours(difficulty: i64) -> (Color, Color) {
+    match difficulty {
+        1 => (Color::Rgb(55, 85, 35), Color::White),
+        2 => (Color::Rgb(1, 252, 122), Color::Black),
+        3 => (Color::Rgb(231, 231, 231), Color::Black),
+        4 => (Color::Rgb(255, 23, 81), Color::White),
+        _ => (Color::Rgb(128, 7, 45), Color::White),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn fixture(event: i64, team_h: i64, team_a: i64) -> Fixture {
+        Fixture { event, team_h, team_a, team_h_difficulty: 2, team_a_difficulty: 4, ..Default::default() }
+    }
+
+    #[test]
+    fn test_schedule_shows_blanks_and_doubles() {
+        let fixtures = vec![fixture(1, 1, 2), fixture(2, 3, 1), fixture(2, 1, 4), fixture(3, 2, 3)];
+        let schedule = schedule(&fixtures, 1, 3);
+        let team_1 = &schedule[&1];
+        assert_eq!(team_1[0], vec![TeamFixture { opponent: 2, is_home: true, difficulty: 2 }]);
+        assert_eq!(team_1[1].len(), 2);
+        assert!(team_1[2].is_empty());
+        assert_eq!(schedule[&3][1], vec![TeamFixture { opponent: 1, is_home: true, difficulty: 2 }]);
+    }
+
+    #[test]
+    fn test_schedule_ignores_gameweeks_outside_the_window() {
+        let fixtures = vec![fixture(1, 1, 2), fixture(5, 1, 2)];
+        let schedule = schedule(&fixtures, 2, 3);
+        assert!(schedule.is_empty());
+    }
+}
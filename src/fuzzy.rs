@@ -0,0 +1,65 @@
+/// Scores how well `query` fuzzily matches `text`, or `None` if it doesn't match at all.
+///
+/// Every character of the query has to appear in the text in order, ignoring case and accents
+/// on common Latin letters. Consecutive matches and matches at the start of a word score higher.
+pub fn score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().map(fold).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.chars().map(fold).filter(|c| !c.is_whitespace()) {
+        let found = (next..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    // Prefer shorter names when the matches are otherwise as good
+    Some(score * 100 - text.len() as i64)
+}
+
+/// Lower case, with the accents the Premier League's player names tend to have removed.
+fn fold(c: char) -> char {
+    let c = c.to_lowercase().next().unwrap_or(c);
+    match c {
+        'á' | 'à' | 'â' | 'ä' | 'ã' | 'å' => 'a',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'ø' => 'o',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'ç' | 'ć' | 'č' => 'c',
+        'ñ' | 'ń' => 'n',
+        'š' | 'ś' => 's',
+        'ž' | 'ź' | 'ż' => 'z',
+        'ğ' => 'g',
+        'ı' => 'i',
+        'ł' => 'l',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_in_order_ignoring_case_and_accents() {
+        assert!(score("odegaard", "Martin Ødegaard").is_some());
+        assert!(score("fernandes", "Bruno Borges Fernandes").is_some());
+        assert!(score("nunez", "Darwin Núñez").is_some());
+        assert!(score("slh", "Mohamed Salah").is_some());
+        assert!(score("hls", "Mohamed Salah").is_none());
+    }
+
+    #[test]
+    fn test_word_starts_and_runs_rank_higher() {
+        let salah = score("sala", "Mohamed Salah").unwrap();
+        let scattered = score("sala", "Sam Lavia").unwrap();
+        assert!(salah > scattered);
+    }
+}
@@ -0,0 +1,276 @@
+use fpl_api::manager::Pick;
+
+/// `element_type` of the assistant manager, who joins the squad in position 16 while the chip is active.
+pub const ASSISTANT_MANAGER: i64 = 5;
+
+/// Squad positions 1 to 11 start; 12 to 15 are the bench, with 12 the reserve goalkeeper.
+pub fn is_starter(pick: &Pick) -> bool {
+    pick.position <= 11
+}
+
+pub fn is_bench(pick: &Pick) -> bool {
+    (12..=15).contains(&pick.position)
+}
+
+/// Why the starting eleven isn't a legal formation, if it isn't.
+///
+/// A lineup needs exactly one goalkeeper, at least three defenders, two midfielders and one forward.
+pub fn formation_error(picks: &[Pick], element_type: impl Fn(i64) -> i64) -> Option<String> {
+    let mut counts = [0; 5];
+    for p in picks.iter().filter(|p| is_starter(p)) {
+        if let Some(count) = counts.get_mut(element_type(p.element) as usize) {
+            *count += 1;
+        }
+    }
+    match counts {
+        [_, gk, ..] if gk != 1 => Some("The lineup needs exactly one goalkeeper".to_string()),
+        [_, _, def, ..] if def < 3 => Some("The lineup needs at least 3 defenders".to_string()),
+        [_, _, _, mid, _] if mid < 2 => Some("The lineup needs at least 2 midfielders".to_string()),
+        [.., fwd] if fwd < 1 => Some("The lineup needs at least 1 forward".to_string()),
+        _ => None,
+    }
+}
+
+/// Swap two players' squad positions, checking that the result is a legal lineup.
+///
+/// The captain and vice-captain have to start, and the reserve goalkeeper's slot stays a
+/// goalkeeper's. The returned picks are sorted by position.
+pub fn swap(picks: &[Pick], a: i64, b: i64, element_type: impl Fn(i64) -> i64) -> Result<Vec<Pick>, String> {
+    let find = |element| picks.iter().position(|p| p.element == element).ok_or("That player isn't in the squad");
+    let (i, j) = (find(a)?, find(b)?);
+    if i == j {
+        return Err("Pick two different players to swap".to_string());
+    }
+    if !(is_starter(&picks[i]) || is_bench(&picks[i])) || !(is_starter(&picks[j]) || is_bench(&picks[j])) {
+        return Err("Only players in the squad can be swapped".to_string());
+    }
+    let mut picks = picks.to_vec();
+    swap_positions(&mut picks, a, b);
+    if is_starter(&picks[i]) != is_starter(&picks[j]) {
+        let (mult_i, mult_j) = (picks[i].multiplier, picks[j].multiplier);
+        picks[i].multiplier = mult_j;
+        picks[j].multiplier = mult_i;
+    }
+    if picks.iter().any(|p| (p.is_captain || p.is_vice_captain) && !is_starter(p)) {
+        return Err("The captain and vice-captain have to start".to_string());
+    }
+    if let Some(e) = formation_error(&picks, &element_type) {
+        return Err(e);
+    }
+    if picks.iter().any(|p| p.position == 12 && element_type(p.element) != 1) {
+        return Err("The first bench slot is for the reserve goalkeeper".to_string());
+    }
+    picks.sort_by_key(|p| p.position);
+    Ok(picks)
+}
+
+/// FPL's automatic substitutions, as `(off, on)` element pairs.
+///
+/// Starters who didn't play are replaced, in squad order, by the first bench player in bench
+/// order who did play and whose coming on keeps a legal formation. A goalkeeper is only replaced
+/// by the reserve goalkeeper. Nobody comes off the bench under Bench Boost, which is spotted by
+/// the bench having multipliers.
+pub fn auto_subs(
+    picks: &[Pick],
+    did_not_play: impl Fn(i64) -> bool,
+    element_type: impl Fn(i64) -> i64,
+) -> Vec<(i64, i64)> {
+    if picks.iter().any(|p| is_bench(p) && p.multiplier > 0) {
+        return Vec::new();
+    }
+    let mut picks = picks.to_vec();
+    picks.sort_by_key(|p| p.position);
+    let missing: Vec<i64> =
+        picks.iter().filter(|p| is_starter(p) && did_not_play(p.element)).map(|p| p.element).collect();
+    let mut subs = Vec::new();
+    for off in missing {
+        let is_goalkeeper = element_type(off) == 1;
+        let bench: Vec<i64> = picks
+            .iter()
+            .filter(|p| is_bench(p) && !did_not_play(p.element) && (element_type(p.element) == 1) == is_goalkeeper)
+            .map(|p| p.element)
+            .collect();
+        for on in bench {
+            let mut trial = picks.clone();
+            swap_positions(&mut trial, off, on);
+            if formation_error(&trial, &element_type).is_none() {
+                picks = trial;
+                subs.push((off, on));
+                break;
+            }
+        }
+    }
+    subs
+}
+
+/// The starting eleven, bench order and armbands that score the most `points`.
+///
+/// The best goalkeeper, three defenders, two midfielders and forward are sure of a start, and the
+/// other four places go to the best of the rest, up to five defenders, five midfielders and three
+/// forwards. The bench is in order of points after the reserve
+/// goalkeeper, and the armbands go to the two best starters. Multipliers from an active chip, a
+/// triple captain or a boosted bench, are kept.
+pub fn best_lineup(picks: &[Pick], points: impl Fn(i64) -> f64, element_type: impl Fn(i64) -> i64) -> Vec<Pick> {
+    let mut ranked: Vec<&Pick> = picks.iter().filter(|p| is_starter(p) || is_bench(p)).collect();
+    ranked.sort_by(|a, b| points(b.element).total_cmp(&points(a.element)).then(a.position.cmp(&b.position)));
+    let mut starters: Vec<&Pick> = Vec::new();
+    for (position, guaranteed) in [(1, 1), (2, 3), (3, 2), (4, 1)] {
+        starters.extend(ranked.iter().filter(|p| element_type(p.element) == position).take(guaranteed));
+    }
+    for p in ranked.iter() {
+        let position = element_type(p.element);
+        let count = starters.iter().filter(|s| element_type(s.element) == position).count();
+        let most = match position {
+            1 => 1,
+            4 => 3,
+            _ => 5,
+        };
+        if starters.len() < 11 && count < most && !starters.contains(p) {
+            starters.push(p);
+        }
+    }
+    let rank = |p: &Pick| ranked.iter().position(|r| r.element == p.element);
+    starters.sort_by_key(|p| (element_type(p.element), rank(p)));
+    let mut bench: Vec<&Pick> = ranked.iter().filter(|p| !starters.contains(p)).copied().collect();
+    bench.sort_by_key(|p| (element_type(p.element) != 1, rank(p)));
+
+    let armband = picks.iter().map(|p| p.multiplier).max().unwrap_or(0).max(2);
+    let bench_multiplier = i64::from(picks.iter().any(|p| is_bench(p) && p.multiplier > 0));
+    let mut by_points = starters.clone();
+    by_points.sort_by_key(|p| rank(p));
+    let captain = by_points.first().map(|p| p.element);
+    let vice = by_points.get(1).map(|p| p.element);
+
+    let mut lineup: Vec<Pick> = starters
+        .iter()
+        .chain(bench.iter())
+        .enumerate()
+        .map(|(i, p)| {
+            let position = i as i64 + 1;
+            let is_captain = Some(p.element) == captain;
+            let multiplier = match (position <= 11, is_captain) {
+                (true, true) => armband,
+                (true, false) => 1,
+                (false, _) => bench_multiplier,
+            };
+            Pick { position, multiplier, is_captain, is_vice_captain: Some(p.element) == vice, ..(*p).clone() }
+        })
+        .collect();
+    lineup.extend(picks.iter().filter(|p| !is_starter(p) && !is_bench(p)).cloned());
+    lineup
+}
+
+fn swap_positions(picks: &mut [Pick], a: i64, b: i64) {
+    let position = |picks: &[Pick], e: i64| picks.iter().find(|p| p.element == e).map(|p| p.position);
+    if let (Some(pos_a), Some(pos_b)) = (position(picks, a), position(picks, b)) {
+        for p in picks.iter_mut() {
+            if p.element == a {
+                p.position = pos_b;
+            } else if p.element == b {
+                p.position = pos_a;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// A 3-4-3 with a GK, DEF, MID, FWD bench, where element `n` plays in squad position `n`.
+    fn squad() -> Vec<Pick> {
+        (1..=15)
+            .map(|n| Pick { element: n, position: n, multiplier: if n <= 11 { 1 } else { 0 }, ..Default::default() })
+            .collect()
+    }
+
+    fn element_type(element: i64) -> i64 {
+        match element {
+            1 | 12 => 1,
+            2..=4 | 13 => 2,
+            5..=8 | 14 => 3,
+            _ => 4,
+        }
+    }
+
+    #[test]
+    fn test_swap_moves_positions_and_multipliers() {
+        let picks = swap(&squad(), 9, 13, element_type).unwrap();
+        let moved = |e| picks.iter().find(|p| p.element == e).map(|p| (p.position, p.multiplier)).unwrap();
+        assert_eq!(moved(13), (9, 1));
+        assert_eq!(moved(9), (13, 0));
+        assert_eq!(formation_error(&picks, element_type), None);
+    }
+
+    #[test]
+    fn test_swap_rejects_illegal_formations() {
+        assert_eq!(swap(&squad(), 2, 14, element_type).unwrap_err(), "The lineup needs at least 3 defenders");
+        assert_eq!(swap(&squad(), 2, 12, element_type).unwrap_err(), "The lineup needs exactly one goalkeeper");
+        assert_eq!(swap(&squad(), 13, 14, element_type).map(|_| ()), Ok(()));
+        assert_eq!(
+            swap(&squad(), 12, 13, element_type).unwrap_err(),
+            "The first bench slot is for the reserve goalkeeper"
+        );
+    }
+
+    #[test]
+    fn test_auto_subs_follow_bench_order_and_formation() {
+        // Two defenders miss out: the DEF on the bench keeps 3 at the back, the MID can't
+        let subs = auto_subs(&squad(), |e| e == 2 || e == 3, element_type);
+        assert_eq!(subs, vec![(2, 13)]);
+        // A forward missing out is replaced by the first outfield player on the bench
+        assert_eq!(auto_subs(&squad(), |e| e == 9, element_type), vec![(9, 13)]);
+    }
+
+    #[test]
+    fn test_auto_subs_only_swap_goalkeepers_for_goalkeepers() {
+        assert_eq!(auto_subs(&squad(), |e| e == 1, element_type), vec![(1, 12)]);
+        assert_eq!(auto_subs(&squad(), |e| e == 1 || e == 12, element_type), vec![]);
+    }
+
+    #[test]
+    fn test_no_auto_subs_under_bench_boost() {
+        let picks: Vec<Pick> = squad().into_iter().map(|p| Pick { multiplier: 1, ..p }).collect();
+        assert_eq!(auto_subs(&picks, |e| e == 9, element_type), vec![]);
+    }
+
+    #[test]
+    fn test_assistant_manager_is_not_on_the_bench() {
+        let mut picks = squad();
+        picks.push(Pick { element: 16, position: 16, multiplier: 1, ..Default::default() });
+        let element_type = |e| if e == 16 { ASSISTANT_MANAGER } else { element_type(e) };
+        assert_eq!(auto_subs(&picks, |e| e == 9, element_type), vec![(9, 13)]);
+        assert_eq!(swap(&picks, 9, 16, element_type).unwrap_err(), "Only players in the squad can be swapped");
+    }
+
+    #[test]
+    fn test_best_lineup_picks_a_legal_eleven_and_armbands() {
+        // Forwards score most, then the bench, but only three forwards can start
+        let points = |element: i64| {
+            match element {
+                9..=11 => 10.0,
+                12..=15 => 5.0 + element as f64 / 100.0,
+                _ => element as f64 / 100.0,
+            }
+        };
+        let lineup = best_lineup(&squad(), points, element_type);
+        assert_eq!(formation_error(&lineup, element_type), None);
+        let at = |position: i64| lineup.iter().find(|p| p.position == position).unwrap();
+        assert_eq!(lineup.iter().filter(|p| is_starter(p)).map(|p| p.element).collect::<Vec<_>>(), vec![
+            12, 13, 4, 3, 14, 8, 7, 6, 9, 10, 11
+        ]);
+        assert_eq!((12..=15).map(|position| at(position).element).collect::<Vec<_>>(), vec![1, 15, 5, 2]);
+        assert_eq!(lineup.iter().find(|p| p.is_captain).map(|p| (p.element, p.multiplier)), Some((9, 2)));
+        assert_eq!(lineup.iter().find(|p| p.is_vice_captain).map(|p| p.element), Some(10));
+        assert!(lineup.iter().filter(|p| is_bench(p)).all(|p| p.multiplier == 0));
+    }
+
+    #[test]
+    fn test_captain_has_to_start() {
+        let mut picks = squad();
+        picks[5].is_captain = true;
+        assert_eq!(swap(&picks, 6, 14, element_type).unwrap_err(), "The captain and vice-captain have to start");
+    }
+}
@@ -3,21 +3,34 @@
 #![allow(unused_variables)]
 
 pub mod action;
-pub mod event;
+pub mod api;
 pub mod app;
 pub mod cli;
+pub mod commands;
 pub mod components;
 pub mod config;
+pub mod data_source;
+pub mod event;
+pub mod fdr;
+pub mod fuzzy;
+pub mod lineup;
 pub mod mode;
+pub mod planner;
+pub mod player_photos;
+pub mod points;
+pub mod price_history;
+pub mod price_predictor;
+pub mod projection;
 pub mod tui;
 pub mod utils;
 
 use clap::Parser;
 use cli::Cli;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{OptionExt, Result};
 
 use crate::{
     app::App,
+    config::Config,
     utils::{initialize_logging, initialize_panic_handler, version},
 };
 
@@ -26,8 +39,25 @@ async fn tokio_main() -> Result<()> {
 
     initialize_panic_handler()?;
 
-    let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate, args.manager_id).await?;
+    let mut args = Cli::parse();
+    let config = Config::new()?;
+    let profile = match args.manager_id {
+        Some(_) => None,
+        None => config.config.profile(args.profile.as_deref())?,
+    };
+    if let Some((_, profile)) = &profile {
+        args.manager_id = Some(profile.manager_id.to_string());
+    }
+    let data_source = data_source::from_args(&args, &config);
+    if let Some(command) = &args.command {
+        return commands::run(command, &args, &config, data_source.as_ref()).await;
+    }
+    let manager_id = args
+        .manager_id
+        .clone()
+        .ok_or_eyre("A manager id is needed, pass one with --manager-id or set a default_profile in the config")?;
+    let mut app =
+        App::new(config, args.tick_rate, args.frame_rate, manager_id, profile, args.gameweek, data_source).await?;
     app.run().await?;
 
     Ok(())
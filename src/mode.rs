@@ -4,4 +4,12 @@ use serde::{Deserialize, Serialize};
 pub enum Mode {
     #[default]
     Home,
+    History,
+    Leagues,
+    Fixtures,
+    Browser,
+    Planner,
+    Prices,
+    /// Not a screen: key bindings under it work in every mode that doesn't bind the same keys.
+    Global,
 }
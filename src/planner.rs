@@ -0,0 +1,365 @@
+//! Transfer planning: the squad rules, free transfers and hits, and drafts saved under the data directory.
+//!
+//! The public API doesn't expose what a manager paid for their players, so players are sold at
+//! their current price rather than FPL's selling price, which keeps half of any rise.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::Result;
+use fpl_api::bootstrap::Element;
+use serde::{Deserialize, Serialize};
+
+use crate::api::ManagerHistory;
+
+pub const SQUAD_SIZE: usize = 15;
+/// Players needed in each `element_type`: goalkeepers, defenders, midfielders and forwards.
+pub const QUOTAS: [usize; 5] = [0, 2, 5, 5, 3];
+pub const MAX_PER_CLUB: usize = 3;
+pub const MAX_FREE_TRANSFERS: i64 = 5;
+pub const HIT_COST: i64 = 4;
+
+/// Why a squad of element ids breaks the FPL squad rules, if it does.
+pub fn squad_error(squad: &[i64], elements: &HashMap<i64, Element>) -> Option<String> {
+    if squad.len() != SQUAD_SIZE {
+        return Some(format!("The squad needs {} players, not {}", SQUAD_SIZE, squad.len()));
+    }
+    let mut positions = [0; 5];
+    let mut clubs: HashMap<i64, usize> = HashMap::new();
+    for (i, id) in squad.iter().enumerate() {
+        let Some(e) = elements.get(id) else {
+            return Some(format!("Player {} is no longer in the game", id));
+        };
+        if squad[..i].contains(id) {
+            return Some(format!("{} is in the squad twice", e.web_name));
+        }
+        if let Some(count) = positions.get_mut(e.element_type as usize) {
+            *count += 1;
+        }
+        *clubs.entry(e.team).or_default() += 1;
+    }
+    const NAMES: [&str; 5] = ["", "goalkeepers", "defenders", "midfielders", "forwards"];
+    if let Some(p) = (1..QUOTAS.len()).find(|&p| positions[p] != QUOTAS[p]) {
+        return Some(format!("The squad needs {} {}, not {}", QUOTAS[p], NAMES[p], positions[p]));
+    }
+    if let Some((_, count)) = clubs.iter().find(|(_, &count)| count > MAX_PER_CLUB) {
+        return Some(format!("No more than {} players from one club, not {}", MAX_PER_CLUB, count));
+    }
+    None
+}
+
+/// Points deducted for making `made` transfers with `free` free ones.
+pub fn hit(free: i64, made: i64) -> i64 {
+    (made - free).max(0) * HIT_COST
+}
+
+/// Free transfers for the next gameweek: whatever wasn't used, plus one, up to the cap.
+pub fn next_free_transfers(free: i64, made: i64) -> i64 {
+    ((free - made).max(0) + 1).min(MAX_FREE_TRANSFERS)
+}
+
+/// Free transfers available for the gameweek after the last one in `history`.
+///
+/// Transfers before a manager's first gameweek are unlimited, and the Wildcard and Free Hit keep
+/// the free transfers that had been saved.
+pub fn free_transfers(history: &ManagerHistory) -> i64 {
+    let mut free = 0;
+    for (i, gw) in history.current.iter().enumerate() {
+        let chip = history.chips.iter().find(|c| c.event == gw.event).map(|c| c.name.as_str());
+        free = match (i, chip) {
+            (0, _) => 1,
+            (_, Some("wildcard" | "freehit")) => free,
+            _ => next_free_transfers(free, gw.event_transfers),
+        };
+    }
+    free.max(1)
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transfer {
+    pub element_out: i64,
+    pub element_in: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedGameweek {
+    pub event: i64,
+    pub transfers: Vec<Transfer>,
+}
+
+/// A transfer plan over the coming gameweeks, starting from a real squad.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    pub name: String,
+    /// The gameweek whose picks the plan starts from.
+    pub base_event: i64,
+    /// Element ids of the squad the plan starts from.
+    pub squad: Vec<i64>,
+    /// Money in the bank, in tenths of a million.
+    pub bank: i64,
+    /// Free transfers for the first planned gameweek.
+    pub free_transfers: i64,
+    pub gameweeks: Vec<PlannedGameweek>,
+}
+
+/// How a plan stands once one of its gameweeks' transfers are made.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub event: i64,
+    pub squad: Vec<i64>,
+    pub bank: i64,
+    /// The squad at current prices, in tenths of a million.
+    pub value: i64,
+    /// Free transfers available going into the gameweek.
+    pub free_transfers: i64,
+    pub hit: i64,
+}
+
+impl Draft {
+    /// A plan with no transfers for `span` gameweeks after `base_event`, stopping at the end of the season.
+    pub fn new(squad: Vec<i64>, bank: i64, free_transfers: i64, base_event: i64, span: i64, last_event: i64) -> Self {
+        let gameweeks = (base_event + 1..=(base_event + span).min(last_event))
+            .map(|event| PlannedGameweek { event, transfers: Vec::new() })
+            .collect();
+        Self { name: String::new(), base_event, squad, bank, free_transfers, gameweeks }
+    }
+
+    pub fn has_transfers(&self) -> bool {
+        self.gameweeks.iter().any(|gw| !gw.transfers.is_empty())
+    }
+
+    /// Play the plan through, gameweek by gameweek, failing at the first broken rule.
+    pub fn outcomes(&self, elements: &HashMap<i64, Element>) -> Result<Vec<Outcome>, String> {
+        let cost = |id: &i64| elements.get(id).map_or(0, |e| e.now_cost);
+        let name = |id: &i64| elements.get(id).map_or_else(|| id.to_string(), |e| e.web_name.clone());
+        let mut squad = self.squad.clone();
+        let mut bank = self.bank;
+        let mut free = self.free_transfers;
+        let mut outcomes = Vec::with_capacity(self.gameweeks.len());
+        for gw in &self.gameweeks {
+            for t in &gw.transfers {
+                let slot = squad
+                    .iter()
+                    .position(|id| *id == t.element_out)
+                    .ok_or_else(|| format!("GW{}: {} isn't in the squad to sell", gw.event, name(&t.element_out)))?;
+                if squad.contains(&t.element_in) {
+                    return Err(format!("GW{}: {} is already in the squad", gw.event, name(&t.element_in)));
+                }
+                bank += cost(&t.element_out) - cost(&t.element_in);
+                squad[slot] = t.element_in;
+            }
+            if let Some(e) = squad_error(&squad, elements) {
+                return Err(format!("GW{}: {}", gw.event, e));
+            }
+            if bank < 0 {
+                return Err(format!("GW{}: £{:.1}m over budget", gw.event, -bank as f64 / 10.0));
+            }
+            let made = gw.transfers.len() as i64;
+            outcomes.push(Outcome {
+                event: gw.event,
+                squad: squad.clone(),
+                bank,
+                value: squad.iter().map(cost).sum(),
+                free_transfers: free,
+                hit: hit(free, made),
+            });
+            free = next_free_transfers(free, made);
+        }
+        Ok(outcomes)
+    }
+
+    /// Add a transfer to the `index`th planned gameweek, as long as the whole plan still holds.
+    pub fn add_transfer(
+        &mut self,
+        index: usize,
+        transfer: Transfer,
+        elements: &HashMap<i64, Element>,
+    ) -> Result<(), String> {
+        let gw = self.gameweeks.get_mut(index).ok_or("That gameweek isn't in the plan")?;
+        gw.transfers.push(transfer);
+        if let Err(e) = self.outcomes(elements) {
+            self.gameweeks[index].transfers.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Take back the last transfer of the `index`th planned gameweek, unless a later one relies on it.
+    pub fn undo_transfer(&mut self, index: usize, elements: &HashMap<i64, Element>) -> Result<(), String> {
+        let gw = self.gameweeks.get_mut(index).ok_or("That gameweek isn't in the plan")?;
+        let transfer = gw.transfers.pop().ok_or_else(|| format!("No transfers planned for GW{}", gw.event))?;
+        if let Err(e) = self.outcomes(elements) {
+            self.gameweeks[index].transfers.push(transfer);
+            return Err(format!("A later gameweek relies on that transfer ({})", e));
+        }
+        Ok(())
+    }
+}
+
+/// Drafts kept as JSON files, one per name, in a directory under the data directory.
+#[derive(Default, Debug, Clone)]
+pub struct Drafts {
+    dir: PathBuf,
+}
+
+impl Drafts {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path_for(&self, name: &str) -> PathBuf {
+        let file: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' }).collect();
+        self.dir.join(format!("{}.json", file))
+    }
+
+    pub fn save(&self, draft: &Draft) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.path_for(&draft.name), serde_json::to_vec_pretty(draft)?)?;
+        Ok(())
+    }
+
+    /// Every readable draft, by name.
+    pub fn list(&self) -> Vec<Draft> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut drafts: Vec<Draft> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| read(&path))
+            .collect();
+        drafts.sort_by(|a, b| a.name.cmp(&b.name));
+        drafts
+    }
+
+    pub fn delete(&self, name: &str) -> Result<()> {
+        std::fs::remove_file(self.path_for(name))?;
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> Option<Draft> {
+    let raw = std::fs::read(path).ok()?;
+    match serde_json::from_slice(&raw) {
+        Ok(draft) => Some(draft),
+        Err(e) => {
+            log::warn!("Skipping unreadable draft {}: {}", path.display(), e);
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_api::manager::EntryHistory;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::api::ChipPlayed;
+
+    /// Two keepers, five defenders, five midfielders and three forwards, two per club, all £5.0m,
+    /// plus a spare of each position at 100 + `element_type` from club 20.
+    fn elements() -> HashMap<i64, Element> {
+        let element_type = |id: i64| {
+            match id {
+                1..=2 => 1,
+                3..=7 => 2,
+                8..=12 => 3,
+                _ => 4,
+            }
+        };
+        (1..=15)
+            .map(|id| (id, element_type(id), (id + 1) / 2))
+            .chain((1..=4).map(|p| (100 + p, p, 20)))
+            .map(|(id, element_type, team)| {
+                (id, Element {
+                    id,
+                    element_type,
+                    team,
+                    now_cost: 50,
+                    web_name: format!("P{}", id),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn draft() -> Draft {
+        Draft::new((1..=15).collect(), 5, 1, 10, 3, 38)
+    }
+
+    #[test]
+    fn test_squad_rules() {
+        let elements = elements();
+        let squad: Vec<i64> = (1..=15).collect();
+        assert_eq!(squad_error(&squad, &elements), None);
+        assert_eq!(squad_error(&squad[1..], &elements), Some("The squad needs 15 players, not 14".to_string()));
+        let mut wrong_position = squad.clone();
+        wrong_position[0] = 104;
+        assert_eq!(squad_error(&wrong_position, &elements), Some("The squad needs 2 goalkeepers, not 1".to_string()));
+
+        let mut crowded = elements.clone();
+        for id in [3, 8] {
+            crowded.get_mut(&id).unwrap().team = 1;
+        }
+        assert_eq!(squad_error(&squad, &crowded), Some("No more than 3 players from one club, not 4".to_string()));
+    }
+
+    #[test]
+    fn test_free_transfers_and_hits() {
+        assert_eq!(hit(1, 1), 0);
+        assert_eq!(hit(1, 3), 8);
+        assert_eq!(next_free_transfers(1, 0), 2);
+        assert_eq!(next_free_transfers(2, 3), 1);
+        assert_eq!(next_free_transfers(5, 0), 5);
+
+        let gw = |event, event_transfers| EntryHistory { event, event_transfers, ..Default::default() };
+        let history = ManagerHistory {
+            current: vec![gw(1, 0), gw(2, 0), gw(3, 0), gw(4, 6), gw(5, 1)],
+            chips: vec![ChipPlayed { name: "wildcard".to_string(), event: 4, ..Default::default() }],
+            ..Default::default()
+        };
+        // 1 after GW1, rolls to 3 by GW4, kept through the wildcard, 1 used in GW5
+        assert_eq!(free_transfers(&history), 3);
+    }
+
+    #[test]
+    fn test_plan_tracks_bank_and_free_transfers() {
+        let mut elements = elements();
+        elements.get_mut(&104).unwrap().now_cost = 52;
+        let mut draft = draft();
+        draft.add_transfer(0, Transfer { element_out: 13, element_in: 104 }, &elements).unwrap();
+        draft.add_transfer(0, Transfer { element_out: 3, element_in: 102 }, &elements).unwrap();
+
+        let outcomes = draft.outcomes(&elements).unwrap();
+        assert_eq!(outcomes.iter().map(|o| (o.event, o.free_transfers, o.hit, o.bank)).collect::<Vec<_>>(), vec![
+            (11, 1, 4, 3),
+            (12, 1, 0, 3),
+            (13, 2, 0, 3)
+        ]);
+    }
+
+    #[test]
+    fn test_plan_rejects_broken_rules() {
+        let mut elements = elements();
+        elements.get_mut(&104).unwrap().now_cost = 56;
+        let mut draft = draft();
+        assert_eq!(
+            draft.add_transfer(0, Transfer { element_out: 13, element_in: 104 }, &elements),
+            Err("GW11: £0.1m over budget".to_string())
+        );
+        assert_eq!(
+            draft.add_transfer(0, Transfer { element_out: 1, element_in: 104 }, &elements),
+            Err("GW11: The squad needs 2 goalkeepers, not 1".to_string())
+        );
+        assert!(!draft.has_transfers());
+
+        elements.get_mut(&104).unwrap().now_cost = 50;
+        draft.add_transfer(0, Transfer { element_out: 13, element_in: 104 }, &elements).unwrap();
+        draft.add_transfer(1, Transfer { element_out: 104, element_in: 13 }, &elements).unwrap();
+        assert!(draft.undo_transfer(0, &elements).is_err());
+        assert_eq!(draft.gameweeks[0].transfers.len(), 1);
+    }
+}
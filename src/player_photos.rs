@@ -0,0 +1,205 @@
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use color_eyre::eyre::{eyre, Result};
+use futures::future::BoxFuture;
+use image::{DynamicImage, ImageReader};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, event::Event};
+
+const PHOTO_URL: &str = "https://resources.premierleague.com/premierleague/photos/players/110x140";
+const MAX_ATTEMPTS: u32 = 4;
+
+fn decode_bytes_to_image(data: Bytes) -> Result<DynamicImage, image::ImageError> {
+    ImageReader::new(std::io::Cursor::new(data)).with_guessed_format()?.decode()
+}
+
+/// Fetches the photo for a player code.
+type Download = Arc<dyn Fn(i64) -> BoxFuture<'static, Result<Bytes>> + Send + Sync>;
+
+async fn download(code: i64) -> Result<Bytes> {
+    let resp = reqwest::get(format!("{}/p{}.png", PHOTO_URL, code)).await?.error_for_status()?;
+    Ok(resp.bytes().await?)
+}
+
+/// Player photos, kept on disk under the data directory once downloaded.
+#[derive(Clone)]
+pub struct PlayerPhotos {
+    dir: PathBuf,
+    /// `None` when offline, so that only photos already on disk are shown.
+    download: Option<Download>,
+    in_flight: Arc<Mutex<HashSet<i64>>>,
+}
+
+impl PlayerPhotos {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_download(dir, Some(Arc::new(|code| Box::pin(download(code)))))
+    }
+
+    /// Photos from the disk cache only, for running without network access.
+    pub fn cached_only(dir: PathBuf) -> Self {
+        Self::with_download(dir, None)
+    }
+
+    fn with_download(dir: PathBuf, download: Option<Download>) -> Self {
+        Self { dir, download, in_flight: Arc::new(Mutex::new(HashSet::new())) }
+    }
+
+    fn path_for(&self, code: i64) -> PathBuf {
+        self.dir.join(format!("p{}.png", code))
+    }
+
+    /// Load the photo for `code` in the background and send it as an [`Event::PlayerImage`].
+    ///
+    /// Does nothing if the same photo is already being loaded. Downloads are retried with
+    /// exponential backoff, and an [`Action::Error`] is sent once they've all failed. Offline, a
+    /// photo that isn't on disk is just left out.
+    pub fn fetch(&self, code: i64, event_tx: UnboundedSender<Event>, action_tx: UnboundedSender<Action>) {
+        if !self.in_flight.lock().unwrap().insert(code) {
+            return;
+        }
+        let photos = self.clone();
+        tokio::spawn(async move {
+            match photos.load(code).await {
+                Ok(Some(image)) => {
+                    let _ = event_tx.send(Event::PlayerImage(code, image));
+                },
+                Ok(None) => log::debug!("No photo on disk for player {} while offline", code),
+                Err(e) => {
+                    let _ = action_tx.send(Action::Error(format!("Unable to load photo for player {}: {}", code, e)));
+                },
+            }
+            photos.in_flight.lock().unwrap().remove(&code);
+        });
+    }
+
+    /// The photo from disk, or downloaded; `None` when it isn't on disk and there's no downloading.
+    async fn load(&self, code: i64) -> Result<Option<DynamicImage>> {
+        let path = self.path_for(code);
+        if let Ok(raw) = tokio::fs::read(&path).await {
+            match decode_bytes_to_image(raw.into()) {
+                Ok(image) => return Ok(Some(image)),
+                Err(e) => log::warn!("Discarding unreadable photo {}: {}", path.display(), e),
+            }
+        }
+
+        let Some(download) = &self.download else {
+            return Ok(None);
+        };
+        let mut delay = Duration::from_secs(1);
+        let mut attempt = 1;
+        let bytes = loop {
+            match download(code).await {
+                Ok(bytes) => break bytes,
+                Err(e) if attempt < MAX_ATTEMPTS => {
+                    log::warn!("Photo download for {} failed (attempt {}): {}", code, attempt, e);
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                },
+                Err(e) => return Err(eyre!("gave up after {} attempts: {}", MAX_ATTEMPTS, e)),
+            }
+        };
+
+        let image = decode_bytes_to_image(bytes.clone())?;
+        if let Err(e) = self.save(&path, &bytes).await {
+            log::warn!("Unable to save photo {}: {}", path.display(), e);
+        }
+        Ok(Some(image))
+    }
+
+    async fn save(&self, path: &PathBuf, bytes: &Bytes) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(path, bytes).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use image::ImageFormat;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    fn png() -> Bytes {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        DynamicImage::new_rgb8(1, 1).write_to(&mut bytes, ImageFormat::Png).unwrap();
+        bytes.into_inner().into()
+    }
+
+    fn photos_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tfpl-photos-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Photos whose downloads take a moment and are counted in `calls`.
+    fn counting(dir: PathBuf, calls: Arc<AtomicUsize>) -> PlayerPhotos {
+        PlayerPhotos::with_download(
+            dir,
+            Some(Arc::new(move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Ok(png())
+                })
+            })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_cached_photo_skips_download() {
+        let dir = photos_dir("cached");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("p7.png"), png()).unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let photos = counting(dir, calls.clone());
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (action_tx, _action_rx) = mpsc::unbounded_channel();
+
+        photos.fetch(7, event_tx, action_tx);
+
+        assert!(matches!(event_rx.recv().await, Some(Event::PlayerImage(7, _))));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_download_once() {
+        let dir = photos_dir("concurrent");
+        let calls = Arc::new(AtomicUsize::new(0));
+        let photos = counting(dir.clone(), calls.clone());
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (action_tx, _action_rx) = mpsc::unbounded_channel();
+
+        photos.fetch(7, event_tx.clone(), action_tx.clone());
+        photos.fetch(7, event_tx, action_tx);
+
+        assert!(matches!(event_rx.recv().await, Some(Event::PlayerImage(7, _))));
+        // Both senders are gone once the one download has finished, so nothing else is coming
+        assert!(event_rx.recv().await.is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(dir.join("p7.png").exists());
+    }
+
+    #[tokio::test]
+    async fn test_offline_misses_send_nothing() {
+        let photos = PlayerPhotos::cached_only(photos_dir("offline"));
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+
+        photos.fetch(7, event_tx, action_tx);
+
+        assert!(event_rx.recv().await.is_none());
+        assert!(action_rx.recv().await.is_none());
+    }
+}
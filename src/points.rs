@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use fpl_api::{
+    bootstrap::Element,
+    fixture::Fixture,
+    manager::Pick,
+    players::{Element as LiveElement, GWLiveData},
+};
+
+use crate::lineup;
+
+/// Live stats for a gameweek, keyed by element id.
+pub fn index_live(live: &GWLiveData) -> HashMap<i64, LiveElement> {
+    live.elements.iter().map(|e| (e.id, e.clone())).collect()
+}
+
+/// Whether a player of `team` has no minutes and no fixture left to get some in `gameweek`.
+pub fn did_not_play(live: Option<&LiveElement>, team: i64, gameweek: i64, fixtures: &[Fixture]) -> bool {
+    if live.is_some_and(|e| e.stats.minutes > 0) {
+        return false;
+    }
+    fixtures
+        .iter()
+        .filter(|f| f.event == gameweek && (f.team_h == team || f.team_a == team))
+        .all(|f| f.finished || f.finished_provisional)
+}
+
+/// Whether the captain missed the gameweek and the vice-captain, who played, took the armband.
+pub fn armband_passed(picks: &[Pick], did_not_play: impl Fn(i64) -> bool) -> bool {
+    let captain = picks.iter().find(|p| p.is_captain);
+    let vice = picks.iter().find(|p| p.is_vice_captain);
+    match (captain, vice) {
+        (Some(c), Some(v)) => did_not_play(c.element) && !did_not_play(v.element),
+        _ => false,
+    }
+}
+
+/// The picks with the positions and multipliers they actually score with.
+///
+/// Automatic substitutions are made first. Then, when the armband passes, the vice-captain takes
+/// the captain's multiplier (including Triple Captain) and the captain drops to a plain starter,
+/// or to the bench if they were substituted.
+pub fn effective_picks(
+    picks: &[Pick],
+    did_not_play: impl Fn(i64) -> bool,
+    element_type: impl Fn(i64) -> i64,
+) -> Vec<Pick> {
+    let mut picks = picks.to_vec();
+    let captain_multiplier = picks.iter().find(|p| p.is_captain).map_or(2, |p| p.multiplier);
+    for (off, on) in lineup::auto_subs(&picks, &did_not_play, element_type) {
+        let position = |e: i64, picks: &[Pick]| picks.iter().find(|p| p.element == e).map_or(0, |p| p.position);
+        let (off_position, on_position) = (position(off, &picks), position(on, &picks));
+        for p in picks.iter_mut() {
+            if p.element == off {
+                p.position = on_position;
+                p.multiplier = 0;
+            } else if p.element == on {
+                p.position = off_position;
+                p.multiplier = 1;
+            }
+        }
+    }
+    if armband_passed(&picks, did_not_play) {
+        for p in picks.iter_mut() {
+            if p.is_captain {
+                p.multiplier = if lineup::is_starter(p) { 1 } else { 0 };
+            } else if p.is_vice_captain {
+                p.multiplier = captain_multiplier;
+            }
+        }
+    }
+    picks
+}
+
+/// [`effective_picks`] in `gameweek`, telling who didn't play from the live stats and the fixtures.
+pub fn live_effective_picks(
+    picks: &[Pick],
+    gameweek: i64,
+    elements: &HashMap<i64, Element>,
+    fixtures: &[Fixture],
+    live: &HashMap<i64, LiveElement>,
+) -> Vec<Pick> {
+    let did_not_play = |element: i64| {
+        elements.get(&element).is_some_and(|e| did_not_play(live.get(&element), e.team, gameweek, fixtures))
+    };
+    let element_type = |element: i64| elements.get(&element).map_or(0, |e| e.element_type);
+    effective_picks(picks, did_not_play, element_type)
+}
+
+/// Gameweek total for a set of picks, before transfer hits.
+///
+/// Each pick's points are scaled by its multiplier, so benched players count for nothing (unless
+/// Bench Boost is active) and the captain counts double, or triple with Triple Captain.
+pub fn gw_total(picks: &[Pick], points_for: impl Fn(i64) -> i64) -> i64 {
+    picks.iter().map(|p| p.multiplier * points_for(p.element)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn pick(element: i64, multiplier: i64) -> Pick {
+        Pick { element, position: element, multiplier, ..Default::default() }
+    }
+
+    #[test]
+    fn test_gw_total_applies_multipliers() {
+        let picks = vec![pick(1, 1), pick(2, 2), pick(3, 0)];
+        assert_eq!(gw_total(&picks, |e| e * 10), 10 + 2 * 20);
+    }
+
+    fn captain(element: i64, multiplier: i64) -> Pick {
+        Pick { is_captain: true, ..pick(element, multiplier) }
+    }
+
+    fn vice(element: i64) -> Pick {
+        Pick { is_vice_captain: true, ..pick(element, 1) }
+    }
+
+    #[test]
+    fn test_vice_captain_takes_the_armband() {
+        let picks = vec![captain(1, 3), vice(2), pick(3, 1)];
+        let effective = effective_picks(&picks, |e| e == 1, |_| 3);
+        assert_eq!(effective.iter().map(|p| p.multiplier).collect::<Vec<_>>(), vec![1, 3, 1]);
+        assert_eq!(gw_total(&effective, |e| if e == 1 { 0 } else { 5 }), 20);
+    }
+
+    #[test]
+    fn test_substituted_captain_passes_the_armband() {
+        // A 3-4-3 with the captain up front and a GK, DEF, MID, FWD bench
+        let mut picks: Vec<Pick> = (1..=15).map(|n| pick(n, if n <= 11 { 1 } else { 0 })).collect();
+        picks[8] = captain(9, 2);
+        picks[9] = vice(10);
+        let element_type = |e| {
+            match e {
+                1 | 12 => 1,
+                2..=4 | 13 => 2,
+                5..=8 | 14 => 3,
+                _ => 4,
+            }
+        };
+        let effective = effective_picks(&picks, |e| e == 9, element_type);
+        let slot = |e| effective.iter().find(|p| p.element == e).map(|p| (p.position, p.multiplier)).unwrap();
+        assert_eq!(slot(9), (13, 0));
+        assert_eq!(slot(13), (9, 1));
+        assert_eq!(slot(10), (10, 2));
+    }
+
+    #[test]
+    fn test_armband_stays_when_vice_captain_also_missed_out() {
+        let picks = vec![captain(1, 2), vice(2)];
+        assert!(!armband_passed(&picks, |_| true));
+        assert_eq!(effective_picks(&picks, |_| true, |_| 3), picks);
+    }
+
+    #[test]
+    fn test_live_effective_picks() {
+        let elements =
+            (1..=2).map(|id| (id, Element { id, team: id, element_type: 3, ..Default::default() })).collect();
+        let fixtures = vec![Fixture { event: 5, team_h: 1, team_a: 3, finished: true, ..Default::default() }];
+        let mut played = LiveElement::default();
+        played.stats.minutes = 90;
+        let live = HashMap::from([(2, played)]);
+        let picks = vec![captain(1, 2), vice(2)];
+
+        let effective = live_effective_picks(&picks, 5, &elements, &fixtures, &live);
+        assert_eq!(effective.iter().map(|p| p.multiplier).collect::<Vec<_>>(), vec![1, 2]);
+        // The captain could still play while their fixture isn't over
+        let fixtures = vec![Fixture { finished: false, ..fixtures[0].clone() }];
+        assert_eq!(live_effective_picks(&picks, 5, &elements, &fixtures, &live), picks);
+    }
+
+    #[test]
+    fn test_gw_total_triple_captain() {
+        let picks = vec![pick(1, 3), pick(2, 1)];
+        assert_eq!(gw_total(&picks, |_| 5), 20);
+    }
+}
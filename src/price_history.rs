@@ -0,0 +1,233 @@
+//! Player prices over time, recorded locally since the API only has today's.
+//!
+//! Every launch and refresh appends a snapshot of each player's price, ownership and gameweek
+//! transfers to `prices.jsonl` under the data directory, one snapshot per line. A snapshot the
+//! same as the one before it isn't written again, and the same bootstrap seen again during a run,
+//! e.g. while looking at a rival's team, isn't even read back.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::Result;
+use fpl_api::bootstrap::BootstrapData;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::mpsc::UnboundedSender};
+
+use crate::{action::Action, event::Event};
+
+/// One player's numbers in a snapshot, with short field names to keep the file small.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlayerPrice {
+    #[serde(rename = "c")]
+    pub now_cost: i64,
+    #[serde(rename = "s")]
+    pub selected_by_percent: f64,
+    #[serde(rename = "i")]
+    pub transfers_in_event: i64,
+    #[serde(rename = "o")]
+    pub transfers_out_event: i64,
+}
+
+/// Every player's price, ownership and transfers at one moment.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceSnapshot {
+    /// Seconds since the Unix epoch.
+    pub taken: u64,
+    /// The gameweek at the time.
+    pub event: i64,
+    pub players: HashMap<i64, PlayerPrice>,
+}
+
+impl PriceSnapshot {
+    pub fn new(bootstrap: &BootstrapData, taken: u64) -> Self {
+        let event = bootstrap.events.iter().find(|e| e.is_current).map_or(0, |e| e.id);
+        let players = bootstrap
+            .elements
+            .iter()
+            .map(|e| {
+                (e.id, PlayerPrice {
+                    now_cost: e.now_cost,
+                    selected_by_percent: e.selected_by_percent.parse().unwrap_or(0.0),
+                    transfers_in_event: e.transfers_in_event,
+                    transfers_out_event: e.transfers_out_event,
+                })
+            })
+            .collect();
+        Self { taken, event, players }
+    }
+}
+
+/// The recorded snapshots, oldest first.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PriceHistory {
+    pub snapshots: Vec<PriceSnapshot>,
+    /// The newest snapshot from before this run, which deltas "since last run" are against.
+    last_run: Option<usize>,
+}
+
+impl PriceHistory {
+    /// Change in price since the last run, in tenths of a million, if the player was around then.
+    pub fn since_last_run(&self, element: i64, now_cost: i64) -> Option<i64> {
+        let before = self.snapshots.get(self.last_run?)?.players.get(&element)?;
+        Some(now_cost - before.now_cost)
+    }
+
+    /// The player's price at each snapshot, as `(taken, now_cost)`.
+    pub fn prices(&self, element: i64) -> Vec<(u64, i64)> {
+        self.snapshots.iter().filter_map(|s| s.players.get(&element).map(|p| (s.taken, p.now_cost))).collect()
+    }
+
+    pub fn latest(&self) -> Option<&PriceSnapshot> {
+        self.snapshots.last()
+    }
+}
+
+/// Price change as shown next to a player, e.g. `+0.1`, or nothing if it hasn't moved.
+pub fn format_change(change: i64) -> String {
+    match change {
+        0 => String::new(),
+        c => format!("{:+.1}", c as f64 / 10.0),
+    }
+}
+
+/// The snapshots file, and when this run started.
+#[derive(Clone)]
+pub struct PriceStore {
+    path: PathBuf,
+    started: u64,
+    /// Keeps a refresh from appending while a launch is still writing.
+    lock: Arc<tokio::sync::Mutex<()>>,
+    /// The players last recorded by this run.
+    recorded: Arc<Mutex<Option<HashMap<i64, PlayerPrice>>>>,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+impl PriceStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, started: now(), lock: Default::default(), recorded: Default::default() }
+    }
+
+    /// Record the prices in `bootstrap` in the background and send the whole history as an [`Event::PriceHistory`].
+    ///
+    /// Does nothing when the prices are the same as the ones last recorded, since the history
+    /// sent then still holds.
+    pub fn record(
+        &self,
+        bootstrap: &BootstrapData,
+        event_tx: UnboundedSender<Event>,
+        action_tx: UnboundedSender<Action>,
+    ) {
+        let snapshot = PriceSnapshot::new(bootstrap, now());
+        {
+            let mut recorded = self.recorded.lock().unwrap();
+            if recorded.as_ref() == Some(&snapshot.players) {
+                return;
+            }
+            *recorded = Some(snapshot.players.clone());
+        }
+        let store = self.clone();
+        tokio::spawn(async move {
+            match store.append(snapshot).await {
+                Ok(history) => {
+                    let _ = event_tx.send(Event::PriceHistory(Arc::new(history)));
+                },
+                Err(e) => {
+                    // Let the next bootstrap try again
+                    *store.recorded.lock().unwrap() = None;
+                    let _ = action_tx.send(Action::Error(format!("Unable to save prices: {}", e)));
+                },
+            }
+        });
+    }
+
+    async fn append(&self, snapshot: PriceSnapshot) -> Result<PriceHistory> {
+        let _guard = self.lock.lock().await;
+        let mut snapshots: Vec<PriceSnapshot> = Vec::new();
+        if let Ok(raw) = tokio::fs::read_to_string(&self.path).await {
+            for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+                match serde_json::from_str(line) {
+                    Ok(snapshot) => snapshots.push(snapshot),
+                    Err(e) => log::warn!("Skipping unreadable price snapshot in {}: {}", self.path.display(), e),
+                }
+            }
+        }
+        if snapshots.last().is_none_or(|last| last.players != snapshot.players) {
+            if let Some(dir) = self.path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let mut line = serde_json::to_string(&snapshot)?;
+            line.push('\n');
+            let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.path).await?;
+            file.write_all(line.as_bytes()).await?;
+            snapshots.push(snapshot);
+        }
+        let last_run = snapshots.iter().rposition(|s| s.taken < self.started);
+        Ok(PriceHistory { snapshots, last_run })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn snapshot(taken: u64, costs: &[(i64, i64)]) -> PriceSnapshot {
+        let players =
+            costs.iter().map(|&(id, now_cost)| (id, PlayerPrice { now_cost, ..Default::default() })).collect();
+        PriceSnapshot { taken, event: 1, players }
+    }
+
+    #[test]
+    fn test_deltas_and_prices() {
+        let history = PriceHistory {
+            snapshots: vec![snapshot(100, &[(1, 50)]), snapshot(200, &[(1, 51), (2, 45)]), snapshot(300, &[(1, 52)])],
+            last_run: Some(1),
+        };
+        assert_eq!(history.since_last_run(1, 52), Some(1));
+        assert_eq!(history.since_last_run(3, 52), None);
+        assert_eq!(history.prices(1), vec![(100, 50), (200, 51), (300, 52)]);
+        assert_eq!(format_change(-1), "-0.1");
+        assert_eq!(format_change(0), "");
+    }
+
+    #[tokio::test]
+    async fn test_store_skips_unchanged_snapshots() {
+        let dir = std::env::temp_dir().join(format!("tfpl-prices-{}", std::process::id()));
+        let store = PriceStore {
+            path: dir.join("prices.jsonl"),
+            started: 150,
+            lock: Default::default(),
+            recorded: Default::default(),
+        };
+        store.append(snapshot(100, &[(1, 50)])).await.unwrap();
+        store.append(snapshot(200, &[(1, 50)])).await.unwrap();
+        let history = store.append(snapshot(300, &[(1, 51)])).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(history.snapshots.iter().map(|s| s.taken).collect::<Vec<_>>(), vec![100, 300]);
+        assert_eq!(history.since_last_run(1, 51), Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_store_records_each_bootstrap_once() {
+        let dir = std::env::temp_dir().join(format!("tfpl-prices-once-{}", std::process::id()));
+        let store = PriceStore::new(dir.join("prices.jsonl"));
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (action_tx, _action_rx) = tokio::sync::mpsc::unbounded_channel();
+        let bootstrap = BootstrapData::default();
+
+        store.record(&bootstrap, event_tx.clone(), action_tx.clone());
+        store.record(&bootstrap, event_tx, action_tx);
+
+        assert!(matches!(event_rx.recv().await, Some(Event::PriceHistory(_))));
+        assert!(event_rx.recv().await.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
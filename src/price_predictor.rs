@@ -0,0 +1,147 @@
+//! Rough odds of each player's price moving, from this gameweek's transfers.
+//!
+//! FPL doesn't publish its formula. Here a player changes price once net transfers since their
+//! last change reach a share of their owners, with a floor so that barely-owned players don't
+//! move on a handful of transfers. The last change is found in the local price history, and
+//! without one the count starts from the beginning of the gameweek.
+
+use std::collections::HashMap;
+
+use fpl_api::bootstrap::Element;
+use ratatui::style::Color;
+
+use crate::price_history::{PriceHistory, PriceSnapshot};
+
+/// Net transfers for a change, as a share of the player's owners.
+const OWNER_SHARE: f64 = 0.08;
+/// The fewest net transfers for a change, as a share of every manager in the game.
+const MANAGER_SHARE: f64 = 0.001;
+/// Progress at which a change counts as close.
+pub const CLOSE: f64 = 0.75;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Prediction {
+    /// Net transfers in since the price last changed, or since the gameweek started.
+    pub net_transfers: i64,
+    /// Net transfers that make a change.
+    pub threshold: f64,
+    /// Progress towards a rise, or a fall when negative, with a change at ±1.
+    pub progress: f64,
+    /// Net transfers an hour between the two latest snapshots of the gameweek.
+    pub per_hour: Option<f64>,
+}
+
+impl Prediction {
+    /// Hours until the change at the current rate, if transfers are heading that way.
+    pub fn hours_to_change(&self) -> Option<f64> {
+        let per_hour = self.per_hour.filter(|r| *r != 0.0)?;
+        let target = self.threshold.copysign(per_hour);
+        let hours = (target - self.net_transfers as f64) / per_hour;
+        (hours >= 0.0).then_some(hours)
+    }
+
+    /// An arrow for a player whose price is close to moving.
+    pub fn indicator(&self) -> Option<(&'static str, Color)> {
+        match self.progress {
+            p if p >= CLOSE => Some(("▲", Color::Green)),
+            p if p <= -CLOSE => Some(("▼", Color::Red)),
+            _ => None,
+        }
+    }
+}
+
+fn net(snapshot: &PriceSnapshot, element: i64) -> Option<(i64, i64)> {
+    snapshot.players.get(&element).map(|p| (p.now_cost, p.transfers_in_event - p.transfers_out_event))
+}
+
+/// Predictions for `elements`, by element id. `total_players` is the number of managers in the game.
+pub fn predict<'a>(
+    elements: impl IntoIterator<Item = &'a Element>,
+    total_players: i64,
+    history: &PriceHistory,
+) -> HashMap<i64, Prediction> {
+    let event = history.latest().map(|s| s.event);
+    let gameweek: Vec<&PriceSnapshot> = history.snapshots.iter().filter(|s| Some(s.event) == event).collect();
+    elements
+        .into_iter()
+        .map(|e| {
+            let net_now = e.transfers_in_event - e.transfers_out_event;
+            // The first snapshot at today's price after it last changed
+            let mut changed = false;
+            let mut since = None;
+            for s in &gameweek {
+                match net(s, e.id) {
+                    Some((cost, _)) if cost != e.now_cost => {
+                        changed = true;
+                        since = None;
+                    },
+                    Some((_, net)) if changed && since.is_none() => since = Some(net),
+                    _ => {},
+                }
+            }
+            let net_transfers = net_now - since.unwrap_or(0);
+
+            let owners = e.selected_by_percent.parse::<f64>().unwrap_or(0.0) / 100.0 * total_players as f64;
+            let threshold = (owners * OWNER_SHARE).max(total_players as f64 * MANAGER_SHARE).max(1.0);
+
+            let recent: Vec<(u64, i64)> =
+                gameweek.iter().rev().filter_map(|s| Some((s.taken, net(s, e.id)?.1))).take(2).collect();
+            let per_hour = match recent.as_slice() {
+                [(t1, n1), (t0, n0)] if t1 > t0 => Some((n1 - n0) as f64 / ((t1 - t0) as f64 / 3600.0)),
+                _ => None,
+            };
+            (e.id, Prediction { net_transfers, threshold, progress: net_transfers as f64 / threshold, per_hour })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::price_history::PlayerPrice;
+
+    fn element(id: i64, now_cost: i64, transfers_in_event: i64, transfers_out_event: i64) -> Element {
+        Element {
+            id,
+            now_cost,
+            transfers_in_event,
+            transfers_out_event,
+            selected_by_percent: "10.0".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(taken: u64, now_cost: i64, transfers_in_event: i64) -> PriceSnapshot {
+        let price = PlayerPrice { now_cost, transfers_in_event, ..Default::default() };
+        PriceSnapshot { taken, event: 5, players: HashMap::from([(1, price)]) }
+    }
+
+    fn history(snapshots: Vec<PriceSnapshot>) -> PriceHistory {
+        let mut history = PriceHistory::default();
+        history.snapshots = snapshots;
+        history
+    }
+
+    #[test]
+    fn test_progress_counts_from_the_gameweek_start() {
+        // 10% of 100,000 managers own the player, so 800 net transfers make a change
+        let predictions = predict(&[element(1, 50, 1_000, 400)], 100_000, &history(Vec::new()));
+        let p = predictions[&1];
+        assert_eq!((p.net_transfers, p.threshold, p.progress), (600, 800.0, 0.75));
+        assert_eq!(p.indicator(), Some(("▲", Color::Green)));
+        assert_eq!(p.per_hour, None);
+    }
+
+    #[test]
+    fn test_progress_restarts_after_a_change() {
+        let snapshots = vec![snapshot(0, 50, 100), snapshot(3_600, 51, 500), snapshot(7_200, 51, 700)];
+        let predictions = predict(&[element(1, 51, 700, 0)], 100_000, &history(snapshots));
+        let p = predictions[&1];
+        assert_eq!(p.net_transfers, 200);
+        assert_eq!(p.per_hour, Some(200.0));
+        assert_eq!(p.hours_to_change(), Some(3.0));
+        assert_eq!(p.indicator(), None);
+    }
+}
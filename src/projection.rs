@@ -0,0 +1,232 @@
+//! Expected points for the rest of the season, from form, minutes, team strength and fixture difficulty.
+//!
+//! A player's points per match blend recent form with points per game scaled by their share of
+//! their team's minutes. Each fixture then scales that by how the relevant team strengths match
+//! up, attack against defence for midfielders and forwards and the other way round for
+//! goalkeepers and defenders, and by its difficulty rating. Doubles add up both fixtures and
+//! blanks score nothing.
+
+use std::collections::HashMap;
+
+use fpl_api::{
+    bootstrap::{BootstrapData, Element, Team},
+    fixture::Fixture,
+};
+
+use crate::fdr::{self, TeamFixture};
+
+/// How much of the points per match comes from form rather than points per game.
+const FORM_WEIGHT: f64 = 0.5;
+/// Change in points for each step of difficulty away from a middling 3.
+const DIFFICULTY_STEP: f64 = 0.1;
+
+fn parse(s: &str) -> f64 {
+    s.parse().unwrap_or(0.0)
+}
+
+/// Projected points for every player in each gameweek from the next one to the end of the season.
+#[derive(Default, Debug, Clone)]
+pub struct Projection {
+    /// The first projected gameweek.
+    pub first: i64,
+    /// How many gameweeks [`Projection::expected`] and [`Projection::upcoming`] look ahead.
+    horizon: usize,
+    points: HashMap<i64, Vec<f64>>,
+}
+
+impl Projection {
+    pub fn new(bootstrap: &BootstrapData, fixtures: &[Fixture], horizon: i64) -> Self {
+        let last = bootstrap.events.iter().map(|e| e.id).max().unwrap_or(0);
+        let first = bootstrap.events.iter().find(|e| e.is_next).map_or(last + 1, |e| e.id);
+        let schedule = fdr::schedule(fixtures, first, (last - first + 1).max(0));
+        let teams: HashMap<i64, &Team> = bootstrap.teams.iter().map(|t| (t.id, t)).collect();
+        let mut played: HashMap<i64, i64> = HashMap::new();
+        for f in fixtures.iter().filter(|f| f.finished) {
+            *played.entry(f.team_h).or_default() += 1;
+            *played.entry(f.team_a).or_default() += 1;
+        }
+
+        let points = bootstrap
+            .elements
+            .iter()
+            .map(|e| {
+                let per_match = per_match(e, played.get(&e.team).copied().unwrap_or(0));
+                let gameweeks = schedule.get(&e.team).map_or(Vec::new(), |gameweeks| {
+                    gameweeks
+                        .iter()
+                        .enumerate()
+                        .map(|(i, fixtures)| {
+                            let chance = availability(e, i);
+                            fixtures.iter().map(|f| per_match * chance * fixture_factor(e, &teams, f)).sum::<f64>()
+                        })
+                        .collect()
+                });
+                (e.id, gameweeks)
+            })
+            .collect();
+        let mut projection = Self { first, horizon: 1, points };
+        projection.set_horizon(horizon);
+        projection
+    }
+
+    pub fn set_horizon(&mut self, horizon: i64) {
+        self.horizon = horizon.max(1) as usize;
+    }
+
+    pub fn horizon(&self) -> usize {
+        self.horizon
+    }
+
+    /// Projected points in `event`, which is nothing for a blank or a gameweek already played.
+    pub fn gameweek(&self, element: i64, event: i64) -> f64 {
+        usize::try_from(event - self.first).ok().and_then(|i| self.points.get(&element)?.get(i)).copied().unwrap_or(0.0)
+    }
+
+    /// Projected points over the next `horizon` gameweeks.
+    pub fn expected(&self, element: i64) -> f64 {
+        self.points.get(&element).map_or(0.0, |p| p.iter().take(self.horizon).sum())
+    }
+
+    /// Projected points for each of the next `horizon` gameweeks, as `(event, points)`.
+    pub fn upcoming(&self, element: i64) -> Vec<(i64, f64)> {
+        self.points.get(&element).map_or(Vec::new(), |p| {
+            p.iter().take(self.horizon).enumerate().map(|(i, points)| (self.first + i as i64, *points)).collect()
+        })
+    }
+}
+
+/// Points a player is expected to score in a middling fixture when fit.
+fn per_match(e: &Element, team_played: i64) -> f64 {
+    let share = match team_played {
+        0 => 1.0,
+        n => (e.minutes as f64 / (90 * n) as f64).min(1.0),
+    };
+    FORM_WEIGHT * parse(&e.form) + (1.0 - FORM_WEIGHT) * parse(&e.points_per_game) * share
+}
+
+/// Chance of the player turning out in the `i`th projected gameweek.
+///
+/// The flagged chance of playing covers the first gameweek. Doubts are assumed to have cleared
+/// after that, while injuries and suspensions are assumed to last.
+fn availability(e: &Element, i: usize) -> f64 {
+    match e.status.as_str() {
+        "a" => 1.0,
+        "d" if i > 0 => 1.0,
+        "u" | "n" => 0.0,
+        _ => e.chance_of_playing_next_round.map_or(0.0, |chance| chance as f64 / 100.0),
+    }
+}
+
+fn fixture_factor(e: &Element, teams: &HashMap<i64, &Team>, f: &TeamFixture) -> f64 {
+    let (Some(own), Some(opponent)) = (teams.get(&e.team), teams.get(&f.opponent)) else {
+        return 1.0;
+    };
+    // Each side's strength at the venue it's playing at
+    let venue = |home: i64, away: i64, is_home: bool| if is_home { home } else { away };
+    let (ours, theirs) = match e.element_type {
+        1 | 2 => {
+            (
+                venue(own.strength_defence_home, own.strength_defence_away, f.is_home),
+                venue(opponent.strength_attack_home, opponent.strength_attack_away, !f.is_home),
+            )
+        },
+        3 | 4 => {
+            (
+                venue(own.strength_attack_home, own.strength_attack_away, f.is_home),
+                venue(opponent.strength_defence_home, opponent.strength_defence_away, !f.is_home),
+            )
+        },
+        _ => {
+            (
+                venue(own.strength_overall_home, own.strength_overall_away, f.is_home),
+                venue(opponent.strength_overall_home, opponent.strength_overall_away, !f.is_home),
+            )
+        },
+    };
+    let strength = match (ours, theirs) {
+        (ours, theirs) if ours > 0 && theirs > 0 => ours as f64 / theirs as f64,
+        _ => 1.0,
+    };
+    strength * (1.0 + (3 - f.difficulty) as f64 * DIFFICULTY_STEP)
+}
+
+#[cfg(test)]
+mod tests {
+    use fpl_api::bootstrap::Event;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn team(id: i64, strength: i64) -> Team {
+        Team {
+            id,
+            strength_attack_home: strength,
+            strength_attack_away: strength,
+            strength_defence_home: strength,
+            strength_defence_away: strength,
+            strength_overall_home: strength,
+            strength_overall_away: strength,
+            ..Default::default()
+        }
+    }
+
+    fn fixture(event: i64, team_h: i64, team_a: i64) -> Fixture {
+        Fixture { event, team_h, team_a, team_h_difficulty: 3, team_a_difficulty: 3, ..Default::default() }
+    }
+
+    /// Gameweek 1 is over and gameweeks 2 to 4 are to come, with team 1 blanking in 3 and doubling in 4.
+    fn bootstrap() -> BootstrapData {
+        let player = |id, team, status: &str| {
+            Element {
+                id,
+                team,
+                element_type: 3,
+                status: status.to_string(),
+                form: "4.0".to_string(),
+                points_per_game: "4.0".to_string(),
+                minutes: 90,
+                ..Default::default()
+            }
+        };
+        BootstrapData {
+            events: (1..=4).map(|id| Event { id, is_next: id == 2, ..Default::default() }).collect(),
+            teams: vec![team(1, 1000), team(2, 1000), team(3, 1250)],
+            elements: vec![player(1, 1, "a"), player(2, 1, "d"), player(3, 2, "a")],
+            ..Default::default()
+        }
+    }
+
+    fn fixtures() -> Vec<Fixture> {
+        vec![
+            Fixture { finished: true, ..fixture(1, 1, 2) },
+            fixture(2, 1, 2),
+            fixture(3, 2, 3),
+            fixture(4, 1, 2),
+            fixture(4, 3, 1),
+        ]
+    }
+
+    #[test]
+    fn test_projection_handles_blanks_and_doubles() {
+        let projection = Projection::new(&bootstrap(), &fixtures(), 3);
+        assert_eq!(projection.first, 2);
+        assert_eq!(projection.gameweek(1, 1), 0.0);
+        assert_eq!(projection.gameweek(1, 2), 4.0);
+        assert_eq!(projection.gameweek(1, 3), 0.0);
+        // Against an even side and then a stronger one
+        assert_eq!(projection.gameweek(1, 4), 4.0 + 4.0 * 1000.0 / 1250.0);
+        assert_eq!(projection.upcoming(1).iter().map(|(gw, _)| *gw).collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_projection_weighs_availability_and_difficulty() {
+        let mut bootstrap = bootstrap();
+        bootstrap.elements[1].chance_of_playing_next_round = Some(50);
+        let mut fixtures = fixtures();
+        fixtures[1].team_a_difficulty = 5;
+        let projection = Projection::new(&bootstrap, &fixtures, 1);
+        assert_eq!(projection.gameweek(2, 2), 2.0);
+        assert_eq!(projection.gameweek(2, 4), projection.gameweek(1, 4));
+        assert_eq!(projection.expected(3), 4.0 * 0.8);
+    }
+}
@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
@@ -143,6 +146,34 @@ macro_rules! trace_dbg {
     };
 }
 
+/// Wall-clock time of day as `HH:MM:SS UTC`.
+pub fn format_utc_time(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default() % (24 * 60 * 60);
+    format!("{:02}:{:02}:{:02} UTC", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// The day of a Unix timestamp as `16/08`.
+pub fn format_utc_date(secs: u64) -> String {
+    // Days since 1970-01-01 to a civil date, from Howard Hinnant's `civil_from_days`
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    format!("{:02}/{:02}", day, month)
+}
+
+/// Kickoff time from the API (`2024-08-16T19:00:00Z`) as `16/08 19:00`, or as given if it doesn't parse.
+pub fn format_kickoff(kickoff_time: &str) -> String {
+    match (kickoff_time.get(5..7), kickoff_time.get(8..10), kickoff_time.get(11..16)) {
+        (Some(month), Some(day), Some(time)) => format!("{}/{} {}", day, month, time),
+        _ => kickoff_time.to_string(),
+    }
+}
+
 pub fn version() -> String {
     let author = clap::crate_authors!();
 